@@ -0,0 +1,325 @@
+//! The area the pen can reach, and what happens to moves that would leave it
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::BlotError;
+use crate::plot::{Point, Polyline};
+
+/// What to do with a move that would take the pen off the bed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutOfBounds {
+    /// Move the pen as far as it can go towards the point, along each axis
+    #[default]
+    Clamp,
+    /// Refuse to make the move at all
+    Reject,
+    /// Draw the part of each line that's on the bed, and lift the pen for the rest
+    Clip,
+}
+
+/// The Blot's work area, in millimetres from the origin at its bottom left corner
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bed {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for Bed {
+    /// The stock Blot's 125×125 mm bed
+    fn default() -> Self {
+        Bed {
+            width: 125.0,
+            height: 125.0,
+        }
+    }
+}
+
+impl Bed {
+    /// Checks that the bed has an area, as moves can't be kept on one that doesn't
+    pub fn validate(&self) -> Result<(), String> {
+        let valid = |size: f32| size.is_finite() && size > 0.0;
+
+        if valid(self.width) && valid(self.height) {
+            Ok(())
+        } else {
+            Err(format!(
+                "The bed must be more than 0 mm wide and high, not {}×{} mm",
+                self.width, self.height
+            ))
+        }
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        (0.0..=self.width).contains(&x) && (0.0..=self.height).contains(&y)
+    }
+
+    /// Returns the point on the bed closest to `point`
+    pub fn clamp(&self, (x, y): Point) -> Point {
+        (x.clamp(0.0, self.width), y.clamp(0.0, self.height))
+    }
+
+    /// Returns the part of the line from `from` to `to` that's on the bed, if any
+    pub fn clip(&self, from: Point, to: Point) -> Option<(Point, Point)> {
+        // Liang-Barsky: narrow down the range of t along the line that's inside each edge
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let edges = [
+            (-dx, from.0),
+            (dx, self.width - from.0),
+            (-dy, from.1),
+            (dy, self.height - from.1),
+        ];
+        let (mut t0, mut t1) = (0.0_f32, 1.0_f32);
+
+        for (p, q) in edges {
+            if p == 0.0 {
+                // Parallel to this edge, so either all of it is inside or none of it is
+                if q < 0.0 {
+                    return None;
+                }
+            } else if p < 0.0 {
+                t0 = t0.max(q / p);
+            } else {
+                t1 = t1.min(q / p);
+            }
+        }
+
+        if t0 > t1 {
+            return None;
+        }
+
+        // Ends that are already inside are kept exactly, so clipped lines still join up
+        let at = |t: f32| {
+            if t == 0.0 {
+                from
+            } else if t == 1.0 {
+                to
+            } else {
+                (from.0 + dx * t, from.1 + dy * t)
+            }
+        };
+        Some((at(t0), at(t1)))
+    }
+
+    /// Decides where a single move to `to` should end up
+    ///
+    /// Clipping needs to know where the pen is coming `from`. Without that, it clamps. A point
+    /// that isn't finite has nowhere on the bed to go, so it's refused whatever the policy.
+    pub fn limit_move(
+        &self,
+        from: Option<Point>,
+        to: Point,
+        policy: OutOfBounds,
+    ) -> Result<Point, BlotError> {
+        if self.contains(to) {
+            return Ok(to);
+        }
+        if !finite(to) {
+            return Err(self.outside(to));
+        }
+
+        match (policy, from) {
+            (OutOfBounds::Reject, _) => Err(self.outside(to)),
+            (OutOfBounds::Clip, Some(from)) => Ok(self
+                .clip(self.clamp(from), to)
+                .map_or_else(|| self.clamp(to), |(_, end)| end)),
+            _ => Ok(self.clamp(to)),
+        }
+    }
+
+    /// Brings a drawing within the bed, or fails if the policy is to reject anything outside it
+    ///
+    /// Drawings with a point that isn't finite are always refused.
+    pub fn limit(
+        &self,
+        polylines: Vec<Polyline>,
+        policy: OutOfBounds,
+    ) -> Result<Vec<Polyline>, BlotError> {
+        if let Some(&p) = polylines.iter().flatten().find(|&&p| !finite(p)) {
+            return Err(self.outside(p));
+        }
+
+        match policy {
+            OutOfBounds::Clamp => Ok(polylines
+                .into_iter()
+                .map(|polyline| polyline.into_iter().map(|p| self.clamp(p)).collect())
+                .collect()),
+            OutOfBounds::Reject => match polylines.iter().flatten().find(|&&p| !self.contains(p)) {
+                Some(&p) => Err(self.outside(p)),
+                None => Ok(polylines),
+            },
+            OutOfBounds::Clip => Ok(polylines
+                .iter()
+                .flat_map(|polyline| self.clip_polyline(polyline))
+                .collect()),
+        }
+    }
+
+    // Splits a polyline into the runs of it that are on the bed
+    fn clip_polyline(&self, polyline: &Polyline) -> Vec<Polyline> {
+        let mut runs: Vec<Polyline> = vec![];
+        let mut current: Polyline = vec![];
+
+        for pair in polyline.windows(2) {
+            let Some((start, end)) = self.clip(pair[0], pair[1]) else {
+                continue;
+            };
+
+            // A line that starts off the bed, or after one that left it, starts a new run
+            if current.last() != Some(&start) {
+                runs.push(std::mem::take(&mut current));
+                current.push(start);
+            }
+            current.push(end);
+        }
+        runs.push(current);
+
+        runs.into_iter().filter(|run| run.len() > 1).collect()
+    }
+
+    fn outside(&self, (x, y): Point) -> BlotError {
+        BlotError::OffBed(format!(
+            "({}, {}) is outside the {}×{} mm bed",
+            x, y, self.width, self.height
+        ))
+    }
+}
+
+fn finite((x, y): Point) -> bool {
+    x.is_finite() && y.is_finite()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BED: Bed = Bed {
+        width: 100.0,
+        height: 50.0,
+    };
+
+    #[test]
+    fn size_must_be_positive_and_finite() {
+        assert!(BED.validate().is_ok());
+        for (width, height) in [
+            (0.0, 50.0),
+            (100.0, -1.0),
+            (f32::NAN, 50.0),
+            (f32::INFINITY, 1.0),
+        ] {
+            assert!(Bed { width, height }.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn clamp_moves_each_axis_onto_the_bed() {
+        assert_eq!(BED.clamp((-5.0, 20.0)), (0.0, 20.0));
+        assert_eq!(BED.clamp((150.0, 60.0)), (100.0, 50.0));
+        assert_eq!(BED.clamp((30.0, 40.0)), (30.0, 40.0));
+    }
+
+    #[test]
+    fn clip_keeps_the_part_on_the_bed() {
+        // Crossing the left and right edges
+        assert_eq!(
+            BED.clip((-50.0, 10.0), (150.0, 10.0)),
+            Some(((0.0, 10.0), (100.0, 10.0)))
+        );
+        // Leaving through the top, diagonally
+        assert_eq!(
+            BED.clip((40.0, 40.0), (60.0, 60.0)),
+            Some(((40.0, 40.0), (50.0, 50.0)))
+        );
+        // Ends inside are kept exactly
+        assert_eq!(
+            BED.clip((1.0, 2.0), (3.0, 4.0)),
+            Some(((1.0, 2.0), (3.0, 4.0)))
+        );
+    }
+
+    #[test]
+    fn clip_misses_lines_off_the_bed() {
+        // Parallel to an edge and outside it
+        assert_eq!(BED.clip((-10.0, 0.0), (-10.0, 50.0)), None);
+        // Passing by a corner
+        assert_eq!(BED.clip((90.0, 70.0), (120.0, 40.0)), None);
+    }
+
+    #[test]
+    fn limit_follows_the_policy() {
+        let drawing = vec![vec![
+            (50.0, 25.0),
+            (150.0, 25.0),
+            (150.0, 40.0),
+            (50.0, 40.0),
+        ]];
+
+        let clamped = BED.limit(drawing.clone(), OutOfBounds::Clamp).unwrap();
+        assert_eq!(
+            clamped,
+            vec![vec![
+                (50.0, 25.0),
+                (100.0, 25.0),
+                (100.0, 40.0),
+                (50.0, 40.0)
+            ]]
+        );
+
+        // The pen lifts where the drawing goes off the bed and comes back down where it returns
+        let clipped = BED.limit(drawing.clone(), OutOfBounds::Clip).unwrap();
+        assert_eq!(
+            clipped,
+            vec![
+                vec![(50.0, 25.0), (100.0, 25.0)],
+                vec![(100.0, 40.0), (50.0, 40.0)]
+            ]
+        );
+
+        assert!(matches!(
+            BED.limit(drawing, OutOfBounds::Reject),
+            Err(BlotError::OffBed(_))
+        ));
+    }
+
+    #[test]
+    fn limit_move_clips_from_where_the_pen_is() {
+        let to = (150.0, 45.0);
+
+        assert_eq!(
+            BED.limit_move(None, to, OutOfBounds::Clip),
+            Ok((100.0, 45.0))
+        );
+        assert_eq!(
+            BED.limit_move(Some((50.0, 5.0)), to, OutOfBounds::Clip),
+            Ok((100.0, 25.0))
+        );
+        assert!(BED
+            .limit_move(Some((50.0, 5.0)), to, OutOfBounds::Reject)
+            .is_err());
+        assert_eq!(
+            BED.limit_move(None, (10.0, 10.0), OutOfBounds::Reject),
+            Ok((10.0, 10.0))
+        );
+    }
+
+    #[test]
+    fn points_that_are_not_finite_are_refused_whatever_the_policy() {
+        for policy in [OutOfBounds::Clamp, OutOfBounds::Reject, OutOfBounds::Clip] {
+            for point in [
+                (f32::NAN, 10.0),
+                (10.0, f32::NAN),
+                (f32::INFINITY, 10.0),
+                (10.0, f32::NEG_INFINITY),
+            ] {
+                assert!(matches!(
+                    BED.limit_move(Some((10.0, 10.0)), point, policy),
+                    Err(BlotError::OffBed(_))
+                ));
+                assert!(matches!(
+                    BED.limit(vec![vec![(10.0, 10.0), point]], policy),
+                    Err(BlotError::OffBed(_))
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Wire traces of a session with a Blot, written as one JSON record per line
+
+use std::fmt;
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::comms::{BlotPacket, PacketState};
+
+/// What happened to a packet when it was captured
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureEvent {
+    /// Queued to be sent, but not sent yet
+    Queued,
+    /// Written to the port, including each time it's resent
+    Sent,
+    /// Read from the port
+    Received,
+    /// Acknowledged by the Blot
+    Resolved,
+    /// Given up on
+    Failed,
+}
+
+/// One line of a capture
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CaptureRecord {
+    /// Seconds since the Unix epoch
+    pub time: f64,
+    pub event: CaptureEvent,
+    pub id: String,
+    pub msg: String,
+    /// The payload as hex, two digits per byte
+    pub payload: String,
+    pub index: Option<u8>,
+    /// The packet's state after the event
+    pub state: String,
+    /// Why the packet failed, for `failed` events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CaptureRecord {
+    /// Decodes the payload back into bytes
+    pub fn payload_bytes(&self) -> Result<Vec<u8>, String> {
+        from_hex(&self.payload)
+    }
+}
+
+/// A file that packets are captured to as they're exchanged
+///
+/// Clones write to the same file, so the client and comms task can both record to it. Each
+/// record is flushed as it's written, so a capture ends where a crash stopped it.
+#[derive(Clone)]
+pub struct Capture {
+    path: PathBuf,
+    writer: Arc<Mutex<LineWriter<File>>>,
+    failed: Arc<AtomicBool>,
+}
+
+impl Capture {
+    /// Starts a capture, replacing whatever was at `path`
+    pub fn create(path: &Path) -> Result<Capture, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Unable to create {}: {}", path.display(), e))?;
+
+        Ok(Capture {
+            path: path.to_path_buf(),
+            writer: Arc::new(Mutex::new(LineWriter::new(file))),
+            failed: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Reads every record from a capture
+    pub fn load(path: &Path) -> Result<Vec<CaptureRecord>, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    format!(
+                        "Invalid record on line {} of {}: {}",
+                        i + 1,
+                        path.display(),
+                        e
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Records a packet as it is after `event`
+    pub fn record(&self, event: CaptureEvent, packet: &BlotPacket) {
+        let (state, error) = match &packet.state {
+            PacketState::Queued => ("Queued", None),
+            PacketState::Sent => ("Sent", None),
+            PacketState::Resolved => ("Resolved", None),
+            PacketState::Received => ("Received", None),
+            PacketState::Failed(e) => ("Failed", Some(e.to_string())),
+        };
+
+        let record = CaptureRecord {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0.0, |t| t.as_secs_f64()),
+            event,
+            id: packet.id.to_string(),
+            msg: packet.msg.clone(),
+            payload: to_hex(&packet.payload),
+            index: packet.index,
+            state: state.to_string(),
+            error,
+        };
+
+        let written = serde_json::to_string(&record)
+            .map_err(|e| e.to_string())
+            .and_then(|line| {
+                writeln!(self.writer.lock().unwrap(), "{}", line).map_err(|e| e.to_string())
+            });
+
+        // Only say so once, rather than for every packet
+        if let Err(e) = written {
+            if !self.failed.swap(true, Ordering::Relaxed) {
+                log::warn!("Unable to write to {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Capture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Capture").field(&self.path).finish()
+    }
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(format!("Odd number of hex digits in {}", hex));
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| format!("Invalid hex in {}", hex))
+        })
+        .collect()
+}
@@ -0,0 +1,403 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::capture::{Capture, CaptureEvent};
+use crate::comms::{
+    self, BlotComms, BlotPacket, CommsOptions, LinkState, LinkStatus, PacketQueue, PacketState,
+    SharedStatus,
+};
+use crate::error::BlotError;
+use crate::plot::Move;
+use crate::transport::{self, Reconnect, Transport};
+
+// Packets that can be waiting for an ack before queueing more holds off until some are acked
+const QUEUE_LIMIT: usize = 64;
+
+/// Servo positions that lift the pen off the paper and put it down on it
+///
+/// Pens of different lengths need different positions to just touch the paper.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PenPositions {
+    pub up: u32,
+    pub down: u32,
+}
+
+impl Default for PenPositions {
+    /// The positions the stock firmware uses
+    fn default() -> Self {
+        PenPositions {
+            up: 1000,
+            down: 1700,
+        }
+    }
+}
+
+/// A connection to a Blot, with a method for each command the stock firmware supports
+///
+/// Each method resolves once the Blot acknowledges the command, and fails if it never does.
+/// Packets are exchanged on a background task, so a Tokio runtime must be running.
+pub struct BlotClient {
+    packet_queue: PacketQueue,
+    comms_task: JoinHandle<()>,
+    status: SharedStatus,
+    window: usize,
+    reconnects: bool,
+    capture: Option<Capture>,
+    pen: PenPositions,
+}
+
+impl BlotClient {
+    /// Opens a port such as `/dev/ttyACM0`, `tcp://host:port` or `sim://`
+    pub fn connect(port: &str, options: CommsOptions) -> Result<BlotClient, BlotError> {
+        let transport = transport::open(port).map_err(|e| BlotError::PortOpen {
+            port: port.to_string(),
+            reason: e.to_string(),
+        })?;
+
+        Ok(BlotClient::spawn(
+            transport,
+            Some(Reconnect::new(port)),
+            options,
+        ))
+    }
+
+    /// Talks to a Blot over an already open transport, which can't be reconnected if it's lost
+    pub fn new(transport: Box<dyn Transport>, options: CommsOptions) -> BlotClient {
+        BlotClient::spawn(transport, None, options)
+    }
+
+    fn spawn(
+        transport: Box<dyn Transport>,
+        reconnect: Option<Reconnect>,
+        options: CommsOptions,
+    ) -> BlotClient {
+        let packet_queue = Arc::new(Mutex::new(VecDeque::new()));
+        let status = Arc::new(std::sync::Mutex::new(LinkStatus {
+            state: LinkState::Connected,
+            dropped_frames: 0,
+        }));
+        let reconnects = reconnect.is_some() && options.reconnect_timeout.is_some();
+        let window = options.window.clamp(1, comms::MAX_WINDOW);
+        let capture = options.capture.clone();
+        let comms_task = tokio::spawn(comms::initialize(
+            transport,
+            reconnect,
+            packet_queue.clone(),
+            options,
+            status.clone(),
+        ));
+
+        BlotClient {
+            packet_queue,
+            comms_task,
+            status,
+            window,
+            reconnects,
+            capture,
+            pen: PenPositions::default(),
+        }
+    }
+
+    /// Sets the servo positions `pen_up` and `pen_down` use, for a pen that needs different ones
+    pub fn set_pen_positions(&mut self, pen: PenPositions) {
+        self.pen = pen;
+    }
+
+    pub fn pen_positions(&self) -> PenPositions {
+        self.pen
+    }
+
+    /// Moves the pen to (x, y), in millimetres from the origin
+    pub async fn go(&self, x: f32, y: f32) -> Result<(), BlotError> {
+        self.send_move(Move::Go(x, y)).await
+    }
+
+    pub async fn pen_up(&self) -> Result<(), BlotError> {
+        self.send_move(Move::PenUp).await
+    }
+
+    pub async fn pen_down(&self) -> Result<(), BlotError> {
+        self.send_move(Move::PenDown).await
+    }
+
+    /// Moves the pen servo to a raw position
+    pub async fn servo(&self, position: u32) -> Result<(), BlotError> {
+        self.send("servo", position.to_le_bytes().to_vec())
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn motors_on(&self) -> Result<(), BlotError> {
+        self.send("motorsOn", vec![]).await.map(|_| ())
+    }
+
+    pub async fn motors_off(&self) -> Result<(), BlotError> {
+        self.send("motorsOff", vec![]).await.map(|_| ())
+    }
+
+    /// Stores the current pen location as the origin
+    pub async fn set_origin(&self) -> Result<(), BlotError> {
+        self.send("setOrigin", vec![]).await.map(|_| ())
+    }
+
+    /// Moves the pen towards the stored origin
+    pub async fn move_towards_origin(&self) -> Result<(), BlotError> {
+        self.send("moveTowardsOrigin", vec![]).await.map(|_| ())
+    }
+
+    /// Sends moves in order, keeping up to a window's worth of them waiting for acks at once
+    ///
+    /// `on_ack` is called with the number of moves acknowledged so far after each ack. If the
+    /// connection drops and comes back, the moves carry on from the last acknowledged one.
+    pub async fn send_moves(
+        &self,
+        moves: &[Move],
+        on_ack: impl FnMut(usize),
+    ) -> Result<(), BlotError> {
+        self.send_moves_from(moves, 0, on_ack).await
+    }
+
+    /// Carries on sending moves from index `start`, such as for a plot that was interrupted
+    ///
+    /// The pen is lifted and put back where the moves before `start` left it first, then the
+    /// rest are sent like [`BlotClient::send_moves`]. The counts passed to `on_ack` include
+    /// the moves before `start`.
+    pub async fn send_moves_from(
+        &self,
+        moves: &[Move],
+        start: usize,
+        mut on_ack: impl FnMut(usize),
+    ) -> Result<(), BlotError> {
+        let start = start.min(moves.len());
+        if start > 0 {
+            self.resume(&moves[..start], moves.len()).await?;
+        }
+
+        let mut unacked = VecDeque::new();
+        let mut queued = start;
+        let mut acked = start;
+
+        while acked < moves.len() {
+            // Keep one more queued than can be in flight, so the comms task has the next
+            // move ready as soon as an ack comes in
+            if queued < moves.len() && unacked.len() <= self.window {
+                let (msg, payload) = self.move_packet(moves[queued]);
+                unacked.push_back(self.queue(msg, payload).await?);
+                queued += 1;
+                continue;
+            }
+
+            let id = unacked.pop_front().unwrap();
+            match self.wait(id).await {
+                Ok(_) => {
+                    acked += 1;
+                    on_ack(acked);
+                }
+                Err(e) if self.reconnects && matches!(e, BlotError::Disconnected(_)) => {
+                    // The rest of the window failed along with it, and gets queued again
+                    for id in unacked.drain(..) {
+                        let _ = self.wait(id).await;
+                    }
+
+                    self.resume(&moves[..acked], moves.len()).await?;
+                    queued = acked;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends any message, for firmware with commands that aren't covered by the other methods
+    pub async fn send(&self, msg: &str, payload: Vec<u8>) -> Result<BlotPacket, BlotError> {
+        let id = self.queue(msg, payload).await?;
+
+        self.wait(id).await
+    }
+
+    /// Queues a message without waiting for the Blot to acknowledge it
+    ///
+    /// Holds off while too many packets are already waiting for acks. Every queued packet
+    /// should be passed to [`BlotClient::wait`], which is also how it leaves the queue.
+    pub async fn queue(&self, msg: &str, payload: Vec<u8>) -> Result<Uuid, BlotError> {
+        let packet = BlotPacket {
+            id: Uuid::new_v4(),
+            msg: msg.to_string(),
+            payload,
+            index: Some(0),
+            state: PacketState::Queued,
+        };
+        // Refuse packets that can't be encoded here, rather than failing the whole connection
+        BlotComms::pack(&packet).map_err(BlotError::Protocol)?;
+
+        let packet = BlotPacket {
+            index: None,
+            ..packet
+        };
+
+        loop {
+            self.check_comms_task()?;
+
+            let mut packets = self.packet_queue.lock().await;
+            let unacked = packets
+                .iter()
+                .filter(|p| matches!(p.state, PacketState::Queued | PacketState::Sent))
+                .count();
+
+            if unacked < QUEUE_LIMIT {
+                let id = packet.id;
+                if let Some(capture) = &self.capture {
+                    capture.record(CaptureEvent::Queued, &packet);
+                }
+                packets.push_back(packet);
+                return Ok(id);
+            }
+
+            // Drop mutex so comms thread can gain a lock
+            std::mem::drop(packets);
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Waits for the Blot to acknowledge a queued packet, and returns it
+    pub async fn wait(&self, id: Uuid) -> Result<BlotPacket, BlotError> {
+        loop {
+            self.check_comms_task()?;
+
+            let mut packets = self.packet_queue.lock().await;
+
+            let Some(position) = packets.iter().position(|p| p.id == id) else {
+                return Err(BlotError::Protocol(format!(
+                    "packet {} isn't in the send queue",
+                    id
+                )));
+            };
+
+            if matches!(
+                packets[position].state,
+                PacketState::Resolved | PacketState::Failed(_)
+            ) {
+                let packet = packets.remove(position).unwrap();
+                return match packet.state {
+                    PacketState::Failed(e) => Err(e),
+                    _ => Ok(packet),
+                };
+            }
+
+            std::mem::drop(packets);
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Number of malformed frames from the Blot that have been skipped so far
+    pub fn dropped_frames(&self) -> u64 {
+        self.status.lock().unwrap().dropped_frames
+    }
+
+    /// Whether the Blot can currently be reached, is being reconnected to or was lost for good
+    pub fn link_state(&self) -> LinkState {
+        self.status.lock().unwrap().state.clone()
+    }
+
+    /// Stops talking to the Blot and closes the port
+    pub async fn close(self) {
+        self.comms_task.abort();
+        // Wait for the comms task to drop its transport so the port is closed cleanly
+        let _ = self.comms_task.await;
+    }
+
+    // Once a lost connection comes back, lifts the pen and puts it back where the last
+    // acknowledged move left it, so the remaining moves can carry on from there
+    async fn resume(&self, done: &[Move], total: usize) -> Result<(), BlotError> {
+        loop {
+            self.wait_for_link().await?;
+
+            let position = done.iter().rev().find_map(|m| match *m {
+                Move::Go(x, y) => Some((x, y)),
+                _ => None,
+            });
+            let pen_down = done.iter().rev().find_map(|m| match m {
+                Move::PenUp => Some(false),
+                Move::PenDown => Some(true),
+                _ => None,
+            }) == Some(true);
+            let (x, y) = position.unwrap_or((0.0, 0.0));
+
+            log::info!(
+                "Resuming from move {}/{} at ({}, {}) with pen {}",
+                done.len(),
+                total,
+                x,
+                y,
+                if pen_down { "down" } else { "up" }
+            );
+
+            let resumed = async {
+                self.pen_up().await?;
+                self.motors_on().await?;
+                self.go(x, y).await?;
+                if pen_down {
+                    self.pen_down().await?;
+                }
+
+                Ok(())
+            }
+            .await;
+
+            // Losing the connection again while resuming means starting over once it's back
+            match resumed {
+                Err(BlotError::Disconnected(_)) => {}
+                result => return result,
+            }
+        }
+    }
+
+    // Waits for the comms task to reconnect, or fails if it gives up
+    async fn wait_for_link(&self) -> Result<(), BlotError> {
+        loop {
+            self.check_comms_task()?;
+
+            match self.link_state() {
+                LinkState::Connected => return Ok(()),
+                LinkState::Reconnecting => {}
+                LinkState::Closed(e) => return Err(e),
+            }
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    async fn send_move(&self, m: Move) -> Result<(), BlotError> {
+        let (msg, payload) = self.move_packet(m);
+
+        self.send(msg, payload).await.map(|_| ())
+    }
+
+    fn move_packet(&self, m: Move) -> (&'static str, Vec<u8>) {
+        match m {
+            Move::PenUp => ("servo", self.pen.up.to_le_bytes().to_vec()),
+            Move::PenDown => ("servo", self.pen.down.to_le_bytes().to_vec()),
+            Move::Go(x, y) => ("go", [x.to_le_bytes(), y.to_le_bytes()].concat()),
+        }
+    }
+
+    // The comms task only stops early if it panicked
+    fn check_comms_task(&self) -> Result<(), BlotError> {
+        if self.comms_task.is_finished() {
+            return Err(BlotError::Disconnected(
+                "the connection task stopped".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -1,21 +1,72 @@
-use std::io::{Read, Write};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, Read, Write};
 use std::str;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio::task::yield_now;
 
 use cobs2::cobs;
-use ringbuffer::{AllocRingBuffer, RingBuffer};
-use serialport;
 use uuid::Uuid;
 
+use crate::capture::{self, Capture, CaptureEvent};
+use crate::error::BlotError;
+use crate::framing::{FrameDecoder, Framing};
+use crate::transport::{Reconnect, Transport};
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum PacketState {
     Queued,
     Sent,
     Resolved,
     Received,
+    Failed(BlotError),
+}
+
+/// Packet indices cycle through 0 to 8, so at most this many packets can be told apart by
+/// their acks at once
+pub const MAX_WINDOW: usize = 8;
+
+/// Most times a packet can be resent, so that every attempt at it can still be counted
+pub const MAX_RETRIES: u8 = u8::MAX - 1;
+
+// How long the line has to be quiet before a partial frame is given up on
+const RESYNC_AFTER: Duration = Duration::from_millis(100);
+
+// Malformed frames in a row after which whatever is on the other end isn't speaking the
+// protocol, rather than a good connection that glitched
+const MAX_CONSECUTIVE_DROPPED: u32 = 10;
+
+// How often to look for a Blot that disconnected
+const RECONNECT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How the connection to a Blot behaves
+#[derive(Clone, Debug)]
+pub struct CommsOptions {
+    /// How long to wait for each ack
+    pub ack_timeout: Duration,
+    /// How many times to resend a packet before giving up on it, up to `MAX_RETRIES`
+    pub retries: u8,
+    /// How many packets can be waiting for acks at once, up to `MAX_WINDOW`
+    pub window: usize,
+    /// How long to wait for a Blot that disconnected to come back, or `None` to give up
+    /// straight away
+    pub reconnect_timeout: Option<Duration>,
+    /// Where to record every packet sent and received, if anywhere
+    pub capture: Option<Capture>,
+}
+
+impl Default for CommsOptions {
+    fn default() -> Self {
+        CommsOptions {
+            ack_timeout: Duration::from_secs(10),
+            retries: 3,
+            window: 4,
+            reconnect_timeout: Some(Duration::from_secs(60)),
+            capture: None,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -27,110 +78,383 @@ pub struct BlotPacket {
     pub state: PacketState,
 }
 
-pub async fn initialize(port: String, packet_queue: Arc<Mutex<AllocRingBuffer<BlotPacket>>>) {
-    let mut comms = BlotComms::initialize(port).expect("Failed to initialize comms");
+// Shows the payload as the stock firmware reads it, or as hex for anything else
+impl fmt::Display for BlotPacket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let payload = &self.payload;
+
+        match (self.msg.as_str(), payload.len()) {
+            ("go", 8) => write!(
+                f,
+                "go x={} y={}",
+                f32::from_le_bytes(payload[0..4].try_into().unwrap()),
+                f32::from_le_bytes(payload[4..8].try_into().unwrap())
+            ),
+            ("servo", 4) => write!(
+                f,
+                "servo {}",
+                u32::from_le_bytes(payload[..].try_into().unwrap())
+            ),
+            (msg, 0) => write!(f, "{}", msg),
+            (msg, _) => write!(f, "{} 0x{}", msg, capture::to_hex(payload)),
+        }
+    }
+}
+
+/// Packets shared between a `BlotClient` and the comms task, oldest first
+pub type PacketQueue = Arc<Mutex<VecDeque<BlotPacket>>>;
+
+/// Whether the comms task can currently reach the Blot
+#[derive(Clone, Debug, PartialEq)]
+pub enum LinkState {
+    Connected,
+    /// The connection was lost, and the port is being opened again until the timeout
+    Reconnecting,
+    /// The connection was lost for good
+    Closed(BlotError),
+}
+
+/// How the connection is doing, shared between a `BlotClient` and the comms task
+#[derive(Clone, Debug)]
+pub struct LinkStatus {
+    pub state: LinkState,
+    /// Number of malformed frames from the Blot that have been skipped
+    pub dropped_frames: u64,
+}
+
+pub type SharedStatus = Arc<std::sync::Mutex<LinkStatus>>;
+
+pub async fn initialize(
+    transport: Box<dyn Transport>,
+    reconnect: Option<Reconnect>,
+    packet_queue: PacketQueue,
+    options: CommsOptions,
+    status: SharedStatus,
+) {
+    let mut transport = transport;
+
+    let error = loop {
+        let mut comms = BlotComms::new(transport, status.clone(), options.capture.clone());
+        let error = exchange(&mut comms, &packet_queue, &options).await;
+        std::mem::drop(comms);
+
+        // Only a lost connection is worth waiting out
+        let (Some(reconnect), Some(timeout), BlotError::Disconnected(_)) =
+            (&reconnect, options.reconnect_timeout, &error)
+        else {
+            break error;
+        };
+
+        log::warn!(
+            "{}. Waiting up to {} seconds for it to come back",
+            error,
+            timeout.as_secs_f32()
+        );
+        status.lock().unwrap().state = LinkState::Reconnecting;
+
+        // Whatever was in flight may never have arrived, so its sender has to decide what to
+        // resend
+        fail_unacked(&mut *packet_queue.lock().await, &error, &options.capture);
+
+        match reopen(reconnect, timeout).await {
+            Some((port, new_transport)) => {
+                log::info!("Reconnected to the Blot on {}", port);
+
+                // Packets queued while the Blot was gone were sent before their sender heard
+                // about it, so they fail too. Holding the queue until the state changes means
+                // everything queued after that is meant for the new connection.
+                let mut packets = packet_queue.lock().await;
+                fail_unacked(&mut packets, &error, &options.capture);
+                status.lock().unwrap().state = LinkState::Connected;
+                transport = new_transport;
+            }
+            None => {
+                log::warn!("Gave up waiting for the Blot to come back");
+                break BlotError::Disconnected(format!(
+                    "the Blot didn't come back within {} seconds",
+                    timeout.as_secs_f32()
+                ));
+            }
+        }
+    };
+
+    // Fail everything sent from now on, as it can no longer reach the Blot
+    status.lock().unwrap().state = LinkState::Closed(error.clone());
+    loop {
+        fail_unacked(&mut *packet_queue.lock().await, &error, &options.capture);
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+fn fail_unacked(packets: &mut VecDeque<BlotPacket>, error: &BlotError, capture: &Option<Capture>) {
+    for packet in packets.iter_mut() {
+        if matches!(packet.state, PacketState::Queued | PacketState::Sent) {
+            packet.state = PacketState::Failed(error.clone());
+            if let Some(capture) = capture {
+                capture.record(CaptureEvent::Failed, packet);
+            }
+        }
+    }
+}
+
+// Keeps trying to open the port again until the timeout runs out
+async fn reopen(reconnect: &Reconnect, timeout: Duration) -> Option<(String, Box<dyn Transport>)> {
+    let deadline = Instant::now() + timeout;
+
+    while Instant::now() < deadline {
+        match reconnect.open() {
+            Ok(reopened) => return Some(reopened),
+            Err(e) => log::debug!("Unable to reopen the Blot's port yet: {}", e),
+        }
+
+        tokio::time::sleep(RECONNECT_INTERVAL).await;
+    }
+
+    None
+}
+
+// Sends queued packets and matches up their acks until something goes wrong with the connection
+async fn exchange(
+    comms: &mut BlotComms,
+    packet_queue: &Mutex<VecDeque<BlotPacket>>,
+    options: &CommsOptions,
+) -> BlotError {
+    let window = options.window.clamp(1, MAX_WINDOW);
+    // When each unacknowledged packet's ack timer started, and how many times it has been sent
+    let mut in_flight: HashMap<Uuid, (Instant, u8)> = HashMap::new();
+    // The oldest unacknowledged packet, the only one whose ack is due
+    let mut oldest: Option<Uuid> = None;
+    // Indices of packets that were resent or given up on, held back until a late ack for an
+    // earlier send can no longer arrive and be mistaken for an ack of a packet reusing them
+    let mut held: HashMap<u8, Instant> = HashMap::new();
+    let mut last_index = 0;
 
     loop {
-        let packet_result = comms.read();
+        let packet_result = match comms.read() {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
         let mut packets = packet_queue.lock().await;
 
-        match packet_result {
-            Some(packet) => match packet.msg.as_str() {
+        if let Some(packet) = packet_result {
+            match packet.msg.as_str() {
                 "ack" => {
                     let sent_packet = packets
                         .iter_mut()
                         .find(|p| p.index == packet.index && p.state == PacketState::Sent);
 
                     match sent_packet {
-                        Some(p) => p.state = PacketState::Resolved,
-                        None => println!("Received an ack for a nonexistent packet"),
+                        Some(p) => {
+                            p.state = PacketState::Resolved;
+                            if let Some((_, attempts)) = in_flight.remove(&p.id) {
+                                if attempts > 1 {
+                                    held.insert(p.index.unwrap(), Instant::now());
+                                }
+                            }
+                            comms.capture(CaptureEvent::Resolved, p);
+                        }
+                        // The ack for a send that was already acked, or given up on
+                        None if packet.index.is_some_and(|i| held.contains_key(&i)) => {}
+                        None => log::warn!(
+                            "Received an ack for index {} with no packet waiting on it",
+                            packet.index.unwrap_or_default()
+                        ),
                     }
                 }
-                _ => {
-                    panic!("Unexpected packet msg: {}", packet.msg)
+                _ => return BlotError::Protocol(format!("expected an ack but got {}", packet.msg)),
+            }
+        }
+
+        // The Blot carries out packets in order, so a packet sent ahead isn't due to be acked
+        // until the ones before it have been. Its timer only starts once it's the oldest.
+        let unacked = packets.iter_mut().find(|p| p.state == PacketState::Sent);
+
+        if let Some(packet) = unacked {
+            if oldest != Some(packet.id) {
+                oldest = Some(packet.id);
+                if let Some((started, _)) = in_flight.get_mut(&packet.id) {
+                    *started = Instant::now();
                 }
-            },
-            None => {
-                let packets_vec = packets.to_vec();
-                let last_packet = packets_vec
-                    .iter()
-                    .filter(|p| p.state != PacketState::Queued)
-                    .last();
-                let mut index = match last_packet {
-                    Some(p) => p.index.unwrap_or(0),
-                    None => 0,
-                };
-                let mut to_send: Vec<&mut BlotPacket> = packets
-                    .iter_mut()
-                    .filter(|p| p.state == PacketState::Queued)
-                    .collect();
-
-                for packet in to_send.iter_mut() {
-                    index = (index + 1) % 9;
-                    packet.index = Some(index);
-                    comms.send(*packet).await.expect("Failed to send message");
-                    packet.state = PacketState::Sent;
+            }
+
+            // Resend a packet that wasn't acked in time with the same index, so the Blot's
+            // ack still matches it, until it runs out of retries
+            if let Some((started, attempts)) = in_flight.get_mut(&packet.id) {
+                if started.elapsed() >= options.ack_timeout {
+                    if *attempts > options.retries.min(MAX_RETRIES) {
+                        packet.state = PacketState::Failed(BlotError::Timeout {
+                            msg: packet.msg.clone(),
+                            attempts: *attempts,
+                        });
+                        in_flight.remove(&packet.id);
+                        held.insert(packet.index.unwrap(), Instant::now());
+                        comms.capture(CaptureEvent::Failed, packet);
+                    } else {
+                        if let Err(e) = comms.send(packet).await {
+                            return e;
+                        }
+                        comms.capture(CaptureEvent::Sent, packet);
+                        *started = Instant::now();
+                        *attempts += 1;
+                    }
                 }
             }
         }
 
+        // Send queued packets in order while there's room in the window. An index
+        // is only reused once the packet that had it is acked or given up on, and once any
+        // duplicate ack for it has had a full timeout to arrive.
+        held.retain(|_, since| since.elapsed() < options.ack_timeout);
+        let mut in_use: Vec<u8> = packets
+            .iter()
+            .filter(|p| p.state == PacketState::Sent)
+            .filter_map(|p| p.index)
+            .collect();
+        let to_send: Vec<&mut BlotPacket> = packets
+            .iter_mut()
+            .filter(|p| p.state == PacketState::Queued)
+            .take(window.saturating_sub(in_use.len()))
+            .collect();
+
+        for packet in to_send {
+            let Some(index) = (1..=9)
+                .map(|i| (last_index + i) % 9)
+                .find(|i| !in_use.contains(i) && !held.contains_key(i))
+            else {
+                break;
+            };
+            last_index = index;
+            in_use.push(index);
+
+            packet.index = Some(index);
+            if let Err(e) = comms.send(packet).await {
+                return e;
+            }
+            packet.state = PacketState::Sent;
+            comms.capture(CaptureEvent::Sent, packet);
+            in_flight.insert(packet.id, (Instant::now(), 1));
+        }
+
         yield_now().await;
     }
 }
 
 pub struct BlotComms {
-    port: Box<dyn serialport::SerialPort>,
+    port: Box<dyn Transport>,
+    decoder: FrameDecoder,
+    status: SharedStatus,
+    last_received: Instant,
+    consecutive_dropped: u32,
+    capture: Option<Capture>,
 }
 
 impl BlotComms {
-    fn initialize(port: String) -> Result<BlotComms, serialport::Error> {
-        let port = serialport::new(&port, 9600)
-            .timeout(Duration::from_millis(100))
-            .open()?;
-
-        Ok(BlotComms { port })
+    fn new(port: Box<dyn Transport>, status: SharedStatus, capture: Option<Capture>) -> BlotComms {
+        BlotComms {
+            port,
+            // 0x0a (LF) terminates each message from the Blot
+            decoder: FrameDecoder::new(Framing::Line),
+            status,
+            last_received: Instant::now(),
+            consecutive_dropped: 0,
+            capture,
+        }
     }
 
-    fn read(&mut self) -> Option<BlotPacket> {
-        let mut response: Vec<u8> = vec![];
-
-        // 0x0a (LF) terminates each message from the Blot
-        while response.iter().find(|&&b| b == 0x0a).is_none() {
-            // max message length: 1 + 255 + 1 + 255 + 1
-            let mut data: Vec<u8> = vec![0; 513];
-            let result = self.port.read(data.as_mut_slice());
+    fn capture(&self, event: CaptureEvent, packet: &BlotPacket) {
+        if let Some(capture) = &self.capture {
+            capture.record(event, packet);
+        }
+    }
 
-            if result.is_err() {
-                return None;
+    // Reads until a whole packet has arrived, or returns `None` once the line goes quiet.
+    // Malformed frames are counted and skipped rather than treated as errors, unless too many
+    // arrive in a row.
+    fn read(&mut self) -> Result<Option<BlotPacket>, BlotError> {
+        loop {
+            match self.decoder.next_frame() {
+                Some(Ok(packet)) => {
+                    self.consecutive_dropped = 0;
+                    self.capture(CaptureEvent::Received, &packet);
+                    return Ok(Some(packet));
+                }
+                Some(Err(e)) => {
+                    self.drop_frame(e)?;
+                    continue;
+                }
+                None => {}
             }
 
-            let bytes_read = result.unwrap();
-            if bytes_read != 0 {
-                response.extend(data[0..bytes_read].iter());
+            // max message length: 1 + 255 + 1 + 255 + 1 + 1
+            let mut data = [0; 514];
+
+            match self.port.read(&mut data) {
+                // The other end of a socket closed the connection
+                Ok(0) => return Err(BlotError::Disconnected("the port was closed".to_string())),
+                Ok(bytes_read) => {
+                    self.decoder.push(&data[..bytes_read]);
+                    self.last_received = Instant::now();
+                }
+                // Nothing has arrived yet
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::TimedOut
+                            | io::ErrorKind::WouldBlock
+                            | io::ErrorKind::Interrupted
+                    ) =>
+                {
+                    // Whatever is left of a frame isn't going to finish now
+                    if self.last_received.elapsed() >= RESYNC_AFTER {
+                        if let Some(e) = self.decoder.resync() {
+                            self.drop_frame(e)?;
+                            continue;
+                        }
+                    }
+                    return Ok(None);
+                }
+                Err(e) => return Err(BlotError::Disconnected(e.to_string())),
             }
         }
+    }
 
-        let unpacked = Self::unpack(&response);
+    // Counts a skipped frame, and gives up on the connection once too many have been skipped
+    // in a row
+    fn drop_frame(&mut self, error: BlotError) -> Result<(), BlotError> {
+        self.status.lock().unwrap().dropped_frames += 1;
+        self.consecutive_dropped += 1;
 
-        if unpacked.is_err() {
-            None
-        } else {
-            Some(unpacked.unwrap())
+        if self.consecutive_dropped < MAX_CONSECUTIVE_DROPPED {
+            return Ok(());
         }
+
+        let reason = match error {
+            BlotError::Framing(reason) => reason,
+            e => e.to_string(),
+        };
+        Err(BlotError::Framing(format!(
+            "{}, the last of {} in a row",
+            reason, self.consecutive_dropped
+        )))
     }
 
-    async fn send(&mut self, packet: &BlotPacket) -> Result<u8, Box<dyn std::error::Error>> {
-        let packed = Self::pack(&packet)?;
+    async fn send(&mut self, packet: &BlotPacket) -> Result<(), BlotError> {
+        let packed = Self::pack(packet).map_err(BlotError::Protocol)?;
 
-        let mut encoded = cobs::encode_vector(&packed)?;
+        let mut encoded = cobs::encode_vector(&packed)
+            .map_err(|e| BlotError::Protocol(format!("Unable to encode packet: {:?}", e)))?;
         encoded.push(0);
 
-        self.port.write(&encoded)?;
-
-        Ok(packet.index.unwrap())
+        match self.port.write_all(&encoded) {
+            Ok(()) => Ok(()),
+            // A write that times out is resent like a lost packet
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => Ok(()),
+            Err(e) => Err(BlotError::Disconnected(e.to_string())),
+        }
     }
 
-    fn pack(packet: &BlotPacket) -> Result<Vec<u8>, String> {
+    pub fn pack(packet: &BlotPacket) -> Result<Vec<u8>, String> {
         let mut buffer: Vec<u8> = vec![];
 
         if packet.msg.len() > 255 {
@@ -152,22 +476,25 @@ impl BlotComms {
         }
     }
 
-    fn unpack(buf: &[u8]) -> Result<BlotPacket, std::str::Utf8Error> {
-        let msg_length = buf[0];
-        let mut msg_bytes: Vec<u8> = vec![];
-        for n in 1..(msg_length + 1) {
-            msg_bytes.push(buf[n as usize]);
-        }
-        let msg = str::from_utf8(&msg_bytes)?.to_string();
+    pub fn unpack(buf: &[u8]) -> Result<BlotPacket, BlotError> {
+        let truncated = || BlotError::Framing(format!("packet is too short ({} bytes)", buf.len()));
 
-        let payload_length = buf[(msg_length + 1) as usize];
-        let mut payload_bytes: Vec<u8> = vec![];
-        for n in (msg_length + 2)..(msg_length + 1 + payload_length) {
-            payload_bytes.push(buf[n as usize]);
-        }
-        let payload = payload_bytes;
+        let msg_length = *buf.first().ok_or_else(truncated)? as usize;
+        let msg_bytes = buf.get(1..msg_length + 1).ok_or_else(truncated)?;
+        let msg = str::from_utf8(msg_bytes)
+            .map_err(|e| BlotError::Framing(format!("message isn't valid UTF-8: {}", e)))?
+            .to_string();
 
-        let index = Some(buf[(msg_length + 2 + payload_length) as usize]);
+        let payload_length = *buf.get(msg_length + 1).ok_or_else(truncated)? as usize;
+        let payload = buf
+            .get(msg_length + 2..msg_length + 2 + payload_length)
+            .ok_or_else(truncated)?
+            .to_vec();
+
+        let index = Some(
+            *buf.get(msg_length + 2 + payload_length)
+                .ok_or_else(truncated)?,
+        );
 
         Ok(BlotPacket {
             id: Uuid::new_v4(),
@@ -178,3 +505,193 @@ impl BlotComms {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{self, PipeTransport};
+
+    // Plays the Blot's side of a pipe, reading the packets the comms task sends
+    struct Device {
+        pipe: PipeTransport,
+        decoder: FrameDecoder,
+    }
+
+    impl Device {
+        fn next(&mut self) -> BlotPacket {
+            loop {
+                if let Some(packet) = self.decoder.next_packet() {
+                    return packet;
+                }
+
+                let mut data = [0; 64];
+                match self.pipe.read(&mut data) {
+                    Ok(n) => self.decoder.push(&data[..n]),
+                    Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+                    Err(e) => panic!("pipe closed: {}", e),
+                }
+            }
+        }
+
+        fn ack(&mut self, index: Option<u8>) {
+            let ack = BlotPacket {
+                id: Uuid::new_v4(),
+                msg: "ack".to_string(),
+                payload: vec![],
+                index,
+                state: PacketState::Queued,
+            };
+            let mut frame = BlotComms::pack(&ack).unwrap();
+            frame.push(0x0a);
+            self.pipe.write_all(&frame).unwrap();
+        }
+    }
+
+    fn queue(packets: &mut VecDeque<BlotPacket>) -> Uuid {
+        let packet = BlotPacket {
+            id: Uuid::new_v4(),
+            msg: "motorsOn".to_string(),
+            payload: vec![],
+            index: None,
+            state: PacketState::Queued,
+        };
+        let id = packet.id;
+        packets.push_back(packet);
+        id
+    }
+
+    async fn state(packet_queue: &PacketQueue, id: Uuid) -> PacketState {
+        let packets = packet_queue.lock().await;
+        packets.iter().find(|p| p.id == id).unwrap().state.clone()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn late_duplicate_ack_does_not_resolve_reused_index() {
+        let (host, pipe) = transport::pipe();
+        let mut device = Device {
+            pipe,
+            decoder: FrameDecoder::new(Framing::Cobs),
+        };
+
+        let blot = std::thread::spawn(move || {
+            // The first send goes unacknowledged, so it's resent, and then both get acked
+            let first = device.next();
+            let resent = device.next();
+            assert_eq!(first.index, resent.index);
+            device.ack(first.index);
+
+            // Go round every other index, so the next packet would reuse the first one's
+            for _ in 0..8 {
+                let packet = device.next();
+                device.ack(packet.index);
+            }
+
+            let last = device.next();
+            device.ack(first.index);
+            // Keep the pipe open by handing back the device
+            (first.index, last.index, device)
+        });
+
+        let packet_queue: PacketQueue = Arc::new(Mutex::new(VecDeque::new()));
+        let status = Arc::new(std::sync::Mutex::new(LinkStatus {
+            state: LinkState::Connected,
+            dropped_frames: 0,
+        }));
+        let options = CommsOptions {
+            ack_timeout: Duration::from_millis(500),
+            window: 1,
+            reconnect_timeout: None,
+            ..Default::default()
+        };
+        let comms_task = tokio::spawn(initialize(
+            Box::new(host),
+            None,
+            packet_queue.clone(),
+            options,
+            status,
+        ));
+
+        let ids: Vec<Uuid> = {
+            let mut packets = packet_queue.lock().await;
+            (0..10).map(|_| queue(&mut packets)).collect()
+        };
+
+        let (first_index, last_index, _device) =
+            tokio::task::spawn_blocking(move || blot.join().unwrap())
+                .await
+                .unwrap();
+        // Give the comms task time to read the stale ack
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        for id in &ids[..9] {
+            assert_eq!(state(&packet_queue, *id).await, PacketState::Resolved);
+        }
+        assert_ne!(last_index, first_index);
+        assert_eq!(state(&packet_queue, ids[9]).await, PacketState::Sent);
+
+        comms_task.abort();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn most_retries_still_give_up() {
+        let (host, pipe) = transport::pipe();
+        let mut device = Device {
+            pipe,
+            decoder: FrameDecoder::new(Framing::Cobs),
+        };
+
+        // The Blot never acks, so every attempt is sent and then given up on
+        let attempts = usize::from(MAX_RETRIES) + 1;
+        let blot = std::thread::spawn(move || {
+            for _ in 0..attempts {
+                device.next();
+            }
+            device
+        });
+
+        let packet_queue: PacketQueue = Arc::new(Mutex::new(VecDeque::new()));
+        let status = Arc::new(std::sync::Mutex::new(LinkStatus {
+            state: LinkState::Connected,
+            dropped_frames: 0,
+        }));
+        let options = CommsOptions {
+            ack_timeout: Duration::from_millis(1),
+            retries: u8::MAX,
+            window: 1,
+            reconnect_timeout: None,
+            ..Default::default()
+        };
+        let comms_task = tokio::spawn(initialize(
+            Box::new(host),
+            None,
+            packet_queue.clone(),
+            options,
+            status,
+        ));
+
+        let id = queue(&mut *packet_queue.lock().await);
+        let _device = tokio::task::spawn_blocking(move || blot.join().unwrap())
+            .await
+            .unwrap();
+
+        let failed = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let PacketState::Failed(e) = state(&packet_queue, id).await {
+                    break e;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(matches!(
+            failed,
+            BlotError::Timeout {
+                attempts: u8::MAX,
+                ..
+            }
+        ));
+
+        comms_task.abort();
+    }
+}
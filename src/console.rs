@@ -0,0 +1,32 @@
+//! Console output that keeps progress lines and messages from running into each other
+
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Whether the cursor is at the end of a progress line rather than the start of a new line
+static MID_LINE: AtomicBool = AtomicBool::new(false);
+
+/// Prints a message on its own line, moving past a progress line it would run into
+pub fn message(message: impl Display) {
+    if MID_LINE.swap(false, Ordering::Relaxed) {
+        println!();
+    }
+
+    println!("{}", message);
+}
+
+/// Replaces the current progress line, or starts one
+pub fn progress(progress: impl Display) {
+    print!("\r{}", progress);
+    let _ = io::stdout().flush();
+
+    MID_LINE.store(true, Ordering::Relaxed);
+}
+
+/// Leaves the progress line as it is and moves on to the next line
+pub fn finish_progress() {
+    if MID_LINE.swap(false, Ordering::Relaxed) {
+        println!();
+    }
+}
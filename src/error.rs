@@ -0,0 +1,54 @@
+use std::fmt;
+
+/// Everything that can go wrong while talking to a Blot
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlotError {
+    /// The port couldn't be opened, for example because it doesn't exist or is in use
+    PortOpen { port: String, reason: String },
+    /// Bytes from the Blot couldn't be decoded into a packet
+    Framing(String),
+    /// A packet broke the rules of the protocol, such as an unexpected reply from the Blot
+    Protocol(String),
+    /// The Blot never acknowledged a command, even after it was resent
+    Timeout { msg: String, attempts: u8 },
+    /// The connection to the Blot was lost
+    Disconnected(String),
+    /// A move would take the pen off the bed, or to a point that isn't a number
+    OffBed(String),
+}
+
+impl BlotError {
+    /// The process exit code the CLI uses for this error, so scripts can tell failures apart
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BlotError::PortOpen { .. } => 3,
+            BlotError::Framing(_) => 4,
+            BlotError::Protocol(_) => 5,
+            BlotError::Timeout { .. } => 6,
+            BlotError::Disconnected(_) => 7,
+            // The same as a drawing that can't be plotted for any other reason
+            BlotError::OffBed(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for BlotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlotError::PortOpen { port, reason } => {
+                write!(f, "Unable to open {}: {}", port, reason)
+            }
+            BlotError::Framing(reason) => write!(f, "Received a malformed packet: {}", reason),
+            BlotError::Protocol(reason) => write!(f, "Protocol error: {}", reason),
+            BlotError::Timeout { msg, attempts } => write!(
+                f,
+                "The Blot didn't acknowledge {} after {} attempts",
+                msg, attempts
+            ),
+            BlotError::Disconnected(reason) => write!(f, "Lost connection to the Blot: {}", reason),
+            BlotError::OffBed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for BlotError {}
@@ -0,0 +1,211 @@
+//! How long a plot will take, and how far the pen goes during it
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::plot::{self, Move, Point};
+
+/// How quickly a Blot carries out moves, for estimating how long plots take
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MotionModel {
+    /// Speed the pen moves at, in mm per second
+    pub feed_rate: f32,
+    /// Seconds the servo takes to raise or lower the pen
+    pub servo_settle: f32,
+    /// Seconds for a packet to be acknowledged when the Blot has nothing to do, used when it
+    /// isn't measured
+    pub ack_latency: f32,
+}
+
+impl MotionModel {
+    /// Checks that the rates make sense, as a feed rate of 0 would make plots take forever
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.feed_rate.is_finite() && self.feed_rate > 0.0) {
+            return Err(format!(
+                "The feed rate must be more than 0 mm per second, not {}",
+                self.feed_rate
+            ));
+        }
+
+        for (name, seconds) in [
+            ("servo settle time", self.servo_settle),
+            ("ack latency", self.ack_latency),
+        ] {
+            if !(seconds.is_finite() && seconds >= 0.0) {
+                return Err(format!(
+                    "The {} must be 0 seconds or more, not {}",
+                    name, seconds
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for MotionModel {
+    fn default() -> Self {
+        MotionModel {
+            feed_rate: 25.0,
+            servo_settle: 0.2,
+            ack_latency: 0.01,
+        }
+    }
+}
+
+/// What plotting a set of moves involves
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Estimate {
+    /// Millimetres drawn with the pen down
+    pub pen_down_distance: f32,
+    /// Millimetres travelled with the pen up
+    pub pen_up_travel: f32,
+    pub pen_lifts: usize,
+    /// Packets sent, one to turn the motors on and then one for each move
+    pub packets: usize,
+    pub duration: Duration,
+}
+
+/// Estimates plotting `moves` from `start`, sending up to `window` of them ahead
+///
+/// Each move takes as long as the motion or servo needs, or its share of the ack latency
+/// if that's longer. With `window` packets in flight, latency only shows when moves are too
+/// short to keep the Blot busy while acks come back. The model should pass
+/// [`MotionModel::validate`].
+pub fn estimate(
+    moves: &[Move],
+    start: Point,
+    model: &MotionModel,
+    ack_latency: f32,
+    window: usize,
+) -> Estimate {
+    let mut estimate = Estimate {
+        packets: moves.len() + 1,
+        ..Default::default()
+    };
+    let latency_share = ack_latency / window.max(1) as f32;
+    let mut position = start;
+    let mut pen_down = false;
+    // The motors are turned on, and that's acknowledged, before any moves are sent
+    let mut seconds = ack_latency as f64;
+
+    for m in moves {
+        let busy = match *m {
+            Move::PenUp => {
+                if pen_down {
+                    estimate.pen_lifts += 1;
+                }
+                pen_down = false;
+                model.servo_settle
+            }
+            Move::PenDown => {
+                pen_down = true;
+                model.servo_settle
+            }
+            Move::Go(x, y) => {
+                let distance = plot::distance(position, (x, y));
+                position = (x, y);
+
+                if pen_down {
+                    estimate.pen_down_distance += distance;
+                } else {
+                    estimate.pen_up_travel += distance;
+                }
+                distance / model.feed_rate
+            }
+        };
+
+        seconds += busy.max(latency_share) as f64;
+    }
+
+    estimate.duration = Duration::from_secs_f64(seconds);
+    estimate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: MotionModel = MotionModel {
+        feed_rate: 10.0,
+        servo_settle: 0.5,
+        ack_latency: 0.0,
+    };
+
+    // A 30 mm line drawn 40 mm from the start, then the pen lifted
+    fn moves() -> Vec<Move> {
+        vec![
+            Move::Go(40.0, 0.0),
+            Move::PenDown,
+            Move::Go(40.0, 30.0),
+            Move::PenUp,
+        ]
+    }
+
+    fn seconds(estimate: &Estimate) -> f64 {
+        estimate.duration.as_secs_f64()
+    }
+
+    #[test]
+    fn validate_rejects_rates_that_make_no_sense() {
+        assert!(MODEL.validate().is_ok());
+        for feed_rate in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let model = MotionModel { feed_rate, ..MODEL };
+            assert!(model.validate().is_err(), "feed rate {}", feed_rate);
+        }
+        for servo_settle in [-0.1, f32::NAN] {
+            let model = MotionModel {
+                servo_settle,
+                ..MODEL
+            };
+            assert!(model.validate().is_err(), "servo settle {}", servo_settle);
+        }
+    }
+
+    #[test]
+    fn distances_and_lifts() {
+        let estimate = estimate(&moves(), (0.0, 0.0), &MODEL, 0.0, 1);
+
+        assert_eq!(estimate.pen_up_travel, 40.0);
+        assert_eq!(estimate.pen_down_distance, 30.0);
+        assert_eq!(estimate.pen_lifts, 1);
+    }
+
+    #[test]
+    fn packets_include_turning_the_motors_on() {
+        assert_eq!(estimate(&moves(), (0.0, 0.0), &MODEL, 0.0, 1).packets, 5);
+        assert_eq!(estimate(&[], (0.0, 0.0), &MODEL, 0.0, 1).packets, 1);
+    }
+
+    #[test]
+    fn lifting_a_raised_pen_isnt_counted() {
+        let moves = [Move::PenUp, Move::Go(1.0, 0.0), Move::PenUp];
+        assert_eq!(estimate(&moves, (0.0, 0.0), &MODEL, 0.0, 1).pen_lifts, 0);
+    }
+
+    #[test]
+    fn duration_is_motion_and_servo_time() {
+        // 70 mm at 10 mm/s, and two servo moves
+        let estimate = estimate(&moves(), (0.0, 0.0), &MODEL, 0.0, 1);
+        assert!((seconds(&estimate) - 8.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn latency_shows_when_moves_are_short() {
+        let moves = vec![Move::Go(0.1, 0.0); 10];
+
+        // Each 0.01 s move waits 0.1 s for its ack, plus one for the motors
+        let alone = estimate(&moves, (0.0, 0.0), &MODEL, 0.1, 1);
+        assert!((seconds(&alone) - 1.1).abs() < 1e-6);
+
+        // With 4 in flight, each only waits a quarter of that
+        let windowed = estimate(&moves, (0.0, 0.0), &MODEL, 0.1, 4);
+        assert!((seconds(&windowed) - 0.35).abs() < 1e-6);
+
+        // A window of 0 is treated as 1
+        let empty_window = estimate(&moves, (0.0, 0.0), &MODEL, 0.1, 0);
+        assert_eq!(empty_window.duration, alone.duration);
+    }
+}
@@ -0,0 +1,1752 @@
+use std::collections::HashMap;
+
+use crate::plot::{Point, Polyline};
+
+/// Names of the built-in fonts, as used in Hershey's font files
+pub const FONT_NAMES: [&str; 3] = ["futural", "timesr", "scripts"];
+
+// Height of a capital letter in font units, the same in each of the built-in fonts
+const CAP_HEIGHT: f32 = 21.0;
+
+// Hershey Roman Simplex, as (advance width, vertices) for each character from ' ' to '~'.
+// Vertices are x, y pairs with y pointing up from the baseline, and (-1, -1) lifts the pen.
+const SIMPLEX: [(i8, &[i8]); 95] = [
+    (16, &[]),
+    (10, &[5, 21, 5, 7, -1, -1, 5, 2, 4, 1, 5, 0, 6, 1, 5, 2]),
+    (16, &[4, 21, 4, 14, -1, -1, 12, 21, 12, 14]),
+    (
+        21,
+        &[
+            11, 25, 4, -7, -1, -1, 17, 25, 10, -7, -1, -1, 4, 12, 18, 12, -1, -1, 3, 6, 17, 6,
+        ],
+    ),
+    (
+        20,
+        &[
+            8, 25, 8, -4, -1, -1, 12, 25, 12, -4, -1, -1, 17, 18, 15, 20, 12, 21, 8, 21, 5, 20, 3,
+            18, 3, 16, 4, 14, 5, 13, 7, 12, 13, 10, 15, 9, 16, 8, 17, 6, 17, 3, 15, 1, 12, 0, 8, 0,
+            5, 1, 3, 3,
+        ],
+    ),
+    (
+        24,
+        &[
+            21, 21, 3, 0, -1, -1, 8, 21, 10, 19, 10, 17, 9, 15, 7, 14, 5, 14, 3, 16, 3, 18, 4, 20,
+            6, 21, 8, 21, 10, 20, 13, 19, 16, 19, 19, 20, 21, 21, -1, -1, 17, 7, 15, 6, 14, 4, 14,
+            2, 16, 0, 18, 0, 20, 1, 21, 3, 21, 5, 19, 7, 17, 7,
+        ],
+    ),
+    (
+        26,
+        &[
+            23, 12, 23, 13, 22, 14, 21, 14, 20, 13, 19, 11, 17, 6, 15, 3, 13, 1, 11, 0, 7, 0, 5, 1,
+            4, 2, 3, 4, 3, 6, 4, 8, 5, 9, 12, 13, 13, 14, 14, 16, 14, 18, 13, 20, 11, 21, 9, 20, 8,
+            18, 8, 16, 9, 13, 11, 10, 16, 3, 18, 1, 20, 0, 22, 0, 23, 1, 23, 2,
+        ],
+    ),
+    (10, &[5, 19, 4, 20, 5, 21, 6, 20, 6, 18, 5, 16, 4, 15]),
+    (
+        14,
+        &[
+            11, 25, 9, 23, 7, 20, 5, 16, 4, 11, 4, 7, 5, 2, 7, -2, 9, -5, 11, -7,
+        ],
+    ),
+    (
+        14,
+        &[
+            3, 25, 5, 23, 7, 20, 9, 16, 10, 11, 10, 7, 9, 2, 7, -2, 5, -5, 3, -7,
+        ],
+    ),
+    (
+        16,
+        &[8, 21, 8, 9, -1, -1, 3, 18, 13, 12, -1, -1, 13, 18, 3, 12],
+    ),
+    (26, &[13, 18, 13, 0, -1, -1, 4, 9, 22, 9]),
+    (10, &[6, 1, 5, 0, 4, 1, 5, 2, 6, 1, 6, -1, 5, -3, 4, -4]),
+    (26, &[4, 9, 22, 9]),
+    (10, &[5, 2, 4, 1, 5, 0, 6, 1, 5, 2]),
+    (22, &[20, 25, 2, -7]),
+    (
+        20,
+        &[
+            9, 21, 6, 20, 4, 17, 3, 12, 3, 9, 4, 4, 6, 1, 9, 0, 11, 0, 14, 1, 16, 4, 17, 9, 17, 12,
+            16, 17, 14, 20, 11, 21, 9, 21,
+        ],
+    ),
+    (20, &[6, 17, 8, 18, 11, 21, 11, 0]),
+    (
+        20,
+        &[
+            4, 16, 4, 17, 5, 19, 6, 20, 8, 21, 12, 21, 14, 20, 15, 19, 16, 17, 16, 15, 15, 13, 13,
+            10, 3, 0, 17, 0,
+        ],
+    ),
+    (
+        20,
+        &[
+            5, 21, 16, 21, 10, 13, 13, 13, 15, 12, 16, 11, 17, 8, 17, 6, 16, 3, 14, 1, 11, 0, 8, 0,
+            5, 1, 4, 2, 3, 4,
+        ],
+    ),
+    (20, &[13, 21, 3, 7, 18, 7, -1, -1, 13, 21, 13, 0]),
+    (
+        20,
+        &[
+            15, 21, 5, 21, 4, 12, 5, 13, 8, 14, 11, 14, 14, 13, 16, 11, 17, 8, 17, 6, 16, 3, 14, 1,
+            11, 0, 8, 0, 5, 1, 4, 2, 3, 4,
+        ],
+    ),
+    (
+        20,
+        &[
+            16, 18, 15, 20, 12, 21, 10, 21, 7, 20, 5, 17, 4, 12, 4, 7, 5, 3, 7, 1, 10, 0, 11, 0,
+            14, 1, 16, 3, 17, 6, 17, 7, 16, 10, 14, 12, 11, 13, 10, 13, 7, 12, 5, 10, 4, 7,
+        ],
+    ),
+    (20, &[17, 21, 7, 0, -1, -1, 3, 21, 17, 21]),
+    (
+        20,
+        &[
+            8, 21, 5, 20, 4, 18, 4, 16, 5, 14, 7, 13, 11, 12, 14, 11, 16, 9, 17, 7, 17, 4, 16, 2,
+            15, 1, 12, 0, 8, 0, 5, 1, 4, 2, 3, 4, 3, 7, 4, 9, 6, 11, 9, 12, 13, 13, 15, 14, 16, 16,
+            16, 18, 15, 20, 12, 21, 8, 21,
+        ],
+    ),
+    (
+        20,
+        &[
+            16, 14, 15, 11, 13, 9, 10, 8, 9, 8, 6, 9, 4, 11, 3, 14, 3, 15, 4, 18, 6, 20, 9, 21, 10,
+            21, 13, 20, 15, 18, 16, 14, 16, 9, 15, 4, 13, 1, 10, 0, 8, 0, 5, 1, 4, 3,
+        ],
+    ),
+    (
+        10,
+        &[
+            5, 14, 4, 13, 5, 12, 6, 13, 5, 14, -1, -1, 5, 2, 4, 1, 5, 0, 6, 1, 5, 2,
+        ],
+    ),
+    (
+        10,
+        &[
+            5, 14, 4, 13, 5, 12, 6, 13, 5, 14, -1, -1, 6, 1, 5, 0, 4, 1, 5, 2, 6, 1, 6, -1, 5, -3,
+            4, -4,
+        ],
+    ),
+    (24, &[20, 18, 4, 9, 20, 0]),
+    (26, &[4, 12, 22, 12, -1, -1, 4, 6, 22, 6]),
+    (24, &[4, 18, 20, 9, 4, 0]),
+    (
+        18,
+        &[
+            3, 16, 3, 17, 4, 19, 5, 20, 7, 21, 11, 21, 13, 20, 14, 19, 15, 17, 15, 15, 14, 13, 13,
+            12, 9, 10, 9, 7, -1, -1, 9, 2, 8, 1, 9, 0, 10, 1, 9, 2,
+        ],
+    ),
+    (
+        27,
+        &[
+            18, 13, 17, 15, 15, 16, 12, 16, 10, 15, 9, 14, 8, 11, 8, 8, 9, 6, 11, 5, 14, 5, 16, 6,
+            17, 8, -1, -1, 12, 16, 10, 14, 9, 11, 9, 8, 10, 6, 11, 5, -1, -1, 18, 16, 17, 8, 17, 6,
+            19, 5, 21, 5, 23, 7, 24, 10, 24, 12, 23, 15, 22, 17, 20, 19, 18, 20, 15, 21, 12, 21, 9,
+            20, 7, 19, 5, 17, 4, 15, 3, 12, 3, 9, 4, 6, 5, 4, 7, 2, 9, 1, 12, 0, 15, 0, 18, 1, 20,
+            2, 21, 3, -1, -1, 19, 16, 18, 8, 18, 6, 19, 5,
+        ],
+    ),
+    (
+        18,
+        &[9, 21, 1, 0, -1, -1, 9, 21, 17, 0, -1, -1, 4, 7, 14, 7],
+    ),
+    (
+        21,
+        &[
+            4, 21, 4, 0, -1, -1, 4, 21, 13, 21, 16, 20, 17, 19, 18, 17, 18, 15, 17, 13, 16, 12, 13,
+            11, -1, -1, 4, 11, 13, 11, 16, 10, 17, 9, 18, 7, 18, 4, 17, 2, 16, 1, 13, 0, 4, 0,
+        ],
+    ),
+    (
+        21,
+        &[
+            18, 16, 17, 18, 15, 20, 13, 21, 9, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3, 8, 4, 5, 5, 3, 7,
+            1, 9, 0, 13, 0, 15, 1, 17, 3, 18, 5,
+        ],
+    ),
+    (
+        21,
+        &[
+            4, 21, 4, 0, -1, -1, 4, 21, 11, 21, 14, 20, 16, 18, 17, 16, 18, 13, 18, 8, 17, 5, 16,
+            3, 14, 1, 11, 0, 4, 0,
+        ],
+    ),
+    (
+        19,
+        &[
+            4, 21, 4, 0, -1, -1, 4, 21, 17, 21, -1, -1, 4, 11, 12, 11, -1, -1, 4, 0, 17, 0,
+        ],
+    ),
+    (
+        18,
+        &[4, 21, 4, 0, -1, -1, 4, 21, 17, 21, -1, -1, 4, 11, 12, 11],
+    ),
+    (
+        21,
+        &[
+            18, 16, 17, 18, 15, 20, 13, 21, 9, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3, 8, 4, 5, 5, 3, 7,
+            1, 9, 0, 13, 0, 15, 1, 17, 3, 18, 5, 18, 8, -1, -1, 13, 8, 18, 8,
+        ],
+    ),
+    (
+        22,
+        &[4, 21, 4, 0, -1, -1, 18, 21, 18, 0, -1, -1, 4, 11, 18, 11],
+    ),
+    (8, &[4, 21, 4, 0]),
+    (
+        16,
+        &[
+            12, 21, 12, 5, 11, 2, 10, 1, 8, 0, 6, 0, 4, 1, 3, 2, 2, 5, 2, 7,
+        ],
+    ),
+    (
+        21,
+        &[4, 21, 4, 0, -1, -1, 18, 21, 4, 7, -1, -1, 9, 12, 18, 0],
+    ),
+    (17, &[4, 21, 4, 0, -1, -1, 4, 0, 16, 0]),
+    (
+        24,
+        &[
+            4, 21, 4, 0, -1, -1, 4, 21, 12, 0, -1, -1, 20, 21, 12, 0, -1, -1, 20, 21, 20, 0,
+        ],
+    ),
+    (
+        22,
+        &[4, 21, 4, 0, -1, -1, 4, 21, 18, 0, -1, -1, 18, 21, 18, 0],
+    ),
+    (
+        22,
+        &[
+            9, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3, 8, 4, 5, 5, 3, 7, 1, 9, 0, 13, 0, 15, 1, 17, 3,
+            18, 5, 19, 8, 19, 13, 18, 16, 17, 18, 15, 20, 13, 21, 9, 21,
+        ],
+    ),
+    (
+        21,
+        &[
+            4, 21, 4, 0, -1, -1, 4, 21, 13, 21, 16, 20, 17, 19, 18, 17, 18, 14, 17, 12, 16, 11, 13,
+            10, 4, 10,
+        ],
+    ),
+    (
+        22,
+        &[
+            9, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3, 8, 4, 5, 5, 3, 7, 1, 9, 0, 13, 0, 15, 1, 17, 3,
+            18, 5, 19, 8, 19, 13, 18, 16, 17, 18, 15, 20, 13, 21, 9, 21, -1, -1, 12, 4, 18, -2,
+        ],
+    ),
+    (
+        21,
+        &[
+            4, 21, 4, 0, -1, -1, 4, 21, 13, 21, 16, 20, 17, 19, 18, 17, 18, 15, 17, 13, 16, 12, 13,
+            11, 4, 11, -1, -1, 11, 11, 18, 0,
+        ],
+    ),
+    (
+        20,
+        &[
+            17, 18, 15, 20, 12, 21, 8, 21, 5, 20, 3, 18, 3, 16, 4, 14, 5, 13, 7, 12, 13, 10, 15, 9,
+            16, 8, 17, 6, 17, 3, 15, 1, 12, 0, 8, 0, 5, 1, 3, 3,
+        ],
+    ),
+    (16, &[8, 21, 8, 0, -1, -1, 1, 21, 15, 21]),
+    (
+        22,
+        &[
+            4, 21, 4, 6, 5, 3, 7, 1, 10, 0, 12, 0, 15, 1, 17, 3, 18, 6, 18, 21,
+        ],
+    ),
+    (18, &[1, 21, 9, 0, -1, -1, 17, 21, 9, 0]),
+    (
+        24,
+        &[
+            2, 21, 7, 0, -1, -1, 12, 21, 7, 0, -1, -1, 12, 21, 17, 0, -1, -1, 22, 21, 17, 0,
+        ],
+    ),
+    (20, &[3, 21, 17, 0, -1, -1, 17, 21, 3, 0]),
+    (18, &[1, 21, 9, 11, 9, 0, -1, -1, 17, 21, 9, 11]),
+    (
+        20,
+        &[17, 21, 3, 0, -1, -1, 3, 21, 17, 21, -1, -1, 3, 0, 17, 0],
+    ),
+    (
+        14,
+        &[
+            4, 25, 4, -7, -1, -1, 5, 25, 5, -7, -1, -1, 4, 25, 11, 25, -1, -1, 4, -7, 11, -7,
+        ],
+    ),
+    (14, &[0, 21, 14, -3]),
+    (
+        14,
+        &[
+            9, 25, 9, -7, -1, -1, 10, 25, 10, -7, -1, -1, 3, 25, 10, 25, -1, -1, 3, -7, 10, -7,
+        ],
+    ),
+    (
+        16,
+        &[
+            6, 15, 8, 18, 10, 15, -1, -1, 3, 12, 8, 17, 13, 12, -1, -1, 8, 17, 8, 0,
+        ],
+    ),
+    (16, &[0, -2, 16, -2]),
+    (10, &[6, 21, 5, 20, 4, 18, 4, 16, 5, 15, 6, 16, 5, 17]),
+    (
+        19,
+        &[
+            15, 14, 15, 0, -1, -1, 15, 11, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3,
+            6, 1, 8, 0, 11, 0, 13, 1, 15, 3,
+        ],
+    ),
+    (
+        19,
+        &[
+            4, 21, 4, 0, -1, -1, 4, 11, 6, 13, 8, 14, 11, 14, 13, 13, 15, 11, 16, 8, 16, 6, 15, 3,
+            13, 1, 11, 0, 8, 0, 6, 1, 4, 3,
+        ],
+    ),
+    (
+        18,
+        &[
+            15, 11, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13,
+            1, 15, 3,
+        ],
+    ),
+    (
+        19,
+        &[
+            15, 21, 15, 0, -1, -1, 15, 11, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3,
+            6, 1, 8, 0, 11, 0, 13, 1, 15, 3,
+        ],
+    ),
+    (
+        18,
+        &[
+            3, 8, 15, 8, 15, 10, 14, 12, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6,
+            1, 8, 0, 11, 0, 13, 1, 15, 3,
+        ],
+    ),
+    (
+        12,
+        &[10, 21, 8, 21, 6, 20, 5, 17, 5, 0, -1, -1, 2, 14, 9, 14],
+    ),
+    (
+        19,
+        &[
+            15, 14, 15, -2, 14, -5, 13, -6, 11, -7, 8, -7, 6, -6, -1, -1, 15, 11, 13, 13, 11, 14,
+            8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13, 1, 15, 3,
+        ],
+    ),
+    (
+        19,
+        &[
+            4, 21, 4, 0, -1, -1, 4, 10, 7, 13, 9, 14, 12, 14, 14, 13, 15, 10, 15, 0,
+        ],
+    ),
+    (8, &[3, 21, 4, 20, 5, 21, 4, 22, 3, 21, -1, -1, 4, 14, 4, 0]),
+    (
+        10,
+        &[
+            5, 21, 6, 20, 7, 21, 6, 22, 5, 21, -1, -1, 6, 14, 6, -3, 5, -6, 3, -7, 1, -7,
+        ],
+    ),
+    (
+        17,
+        &[4, 21, 4, 0, -1, -1, 14, 14, 4, 4, -1, -1, 8, 8, 15, 0],
+    ),
+    (8, &[4, 21, 4, 0]),
+    (
+        30,
+        &[
+            4, 14, 4, 0, -1, -1, 4, 10, 7, 13, 9, 14, 12, 14, 14, 13, 15, 10, 15, 0, -1, -1, 15,
+            10, 18, 13, 20, 14, 23, 14, 25, 13, 26, 10, 26, 0,
+        ],
+    ),
+    (
+        19,
+        &[
+            4, 14, 4, 0, -1, -1, 4, 10, 7, 13, 9, 14, 12, 14, 14, 13, 15, 10, 15, 0,
+        ],
+    ),
+    (
+        19,
+        &[
+            8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0, 11, 0, 13, 1, 15, 3, 16, 6, 16, 8,
+            15, 11, 13, 13, 11, 14, 8, 14,
+        ],
+    ),
+    (
+        19,
+        &[
+            4, 14, 4, -7, -1, -1, 4, 11, 6, 13, 8, 14, 11, 14, 13, 13, 15, 11, 16, 8, 16, 6, 15, 3,
+            13, 1, 11, 0, 8, 0, 6, 1, 4, 3,
+        ],
+    ),
+    (
+        19,
+        &[
+            15, 14, 15, -7, -1, -1, 15, 11, 13, 13, 11, 14, 8, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3,
+            6, 1, 8, 0, 11, 0, 13, 1, 15, 3,
+        ],
+    ),
+    (
+        13,
+        &[4, 14, 4, 0, -1, -1, 4, 8, 5, 11, 7, 13, 9, 14, 12, 14],
+    ),
+    (
+        17,
+        &[
+            14, 11, 13, 13, 10, 14, 7, 14, 4, 13, 3, 11, 4, 9, 6, 8, 11, 7, 13, 6, 14, 4, 14, 3,
+            13, 1, 10, 0, 7, 0, 4, 1, 3, 3,
+        ],
+    ),
+    (12, &[5, 21, 5, 4, 6, 1, 8, 0, 10, 0, -1, -1, 2, 14, 9, 14]),
+    (
+        19,
+        &[
+            4, 14, 4, 4, 5, 1, 7, 0, 10, 0, 12, 1, 15, 4, -1, -1, 15, 14, 15, 0,
+        ],
+    ),
+    (16, &[2, 14, 8, 0, -1, -1, 14, 14, 8, 0]),
+    (
+        22,
+        &[
+            3, 14, 7, 0, -1, -1, 11, 14, 7, 0, -1, -1, 11, 14, 15, 0, -1, -1, 19, 14, 15, 0,
+        ],
+    ),
+    (17, &[3, 14, 14, 0, -1, -1, 14, 14, 3, 0]),
+    (
+        16,
+        &[
+            2, 14, 8, 0, -1, -1, 14, 14, 8, 0, 6, -4, 4, -6, 2, -7, 1, -7,
+        ],
+    ),
+    (
+        17,
+        &[14, 14, 3, 0, -1, -1, 3, 14, 14, 14, -1, -1, 3, 0, 14, 0],
+    ),
+    (
+        14,
+        &[
+            9, 25, 7, 24, 6, 23, 5, 21, 5, 19, 6, 17, 7, 16, 8, 14, 8, 12, 6, 10, -1, -1, 7, 24, 6,
+            22, 6, 20, 7, 18, 8, 17, 9, 15, 9, 13, 8, 11, 4, 9, 8, 7, 9, 5, 9, 3, 8, 1, 7, 0, 6,
+            -2, 6, -4, 7, -6, -1, -1, 6, 8, 8, 6, 8, 4, 7, 2, 6, 1, 5, -1, 5, -3, 6, -5, 7, -6, 9,
+            -7,
+        ],
+    ),
+    (8, &[4, 25, 4, -7]),
+    (
+        14,
+        &[
+            5, 25, 7, 24, 8, 23, 9, 21, 9, 19, 8, 17, 7, 16, 6, 14, 6, 12, 8, 10, -1, -1, 7, 24, 8,
+            22, 8, 20, 7, 18, 6, 17, 5, 15, 5, 13, 6, 11, 10, 9, 6, 7, 5, 5, 5, 3, 6, 1, 7, 0, 8,
+            -2, 8, -4, 7, -6, -1, -1, 8, 8, 6, 6, 6, 4, 7, 2, 8, 1, 9, -1, 9, -3, 8, -5, 7, -6, 5,
+            -7,
+        ],
+    ),
+    (
+        24,
+        &[
+            3, 6, 3, 8, 4, 11, 6, 12, 8, 12, 10, 11, 14, 8, 16, 7, 18, 7, 20, 8, 21, 10, -1, -1, 3,
+            8, 4, 10, 6, 11, 8, 11, 10, 10, 14, 7, 16, 6, 18, 6, 20, 7, 21, 10, 21, 12,
+        ],
+    ),
+];
+
+// Hershey Roman Complex, in the same form as SIMPLEX
+const COMPLEX: [(i8, &[i8]); 95] = [
+    (16, &[]),
+    (
+        10,
+        &[
+            5, 21, 4, 19, 5, 7, 6, 19, 5, 21, -1, -1, 5, 19, 5, 13, -1, -1, 5, 3, 4, 2, 5, 1, 6, 2,
+            5, 3,
+        ],
+    ),
+    (
+        16,
+        &[
+            4, 21, 4, 14, -1, -1, 5, 21, 5, 14, -1, -1, 12, 21, 12, 14, -1, -1, 13, 21, 13, 14,
+        ],
+    ),
+    (
+        21,
+        &[
+            11, 25, 4, -7, -1, -1, 17, 25, 10, -7, -1, -1, 4, 12, 18, 12, -1, -1, 3, 6, 17, 6,
+        ],
+    ),
+    (
+        20,
+        &[
+            8, 25, 8, -4, -1, -1, 12, 25, 12, -4, -1, -1, 16, 18, 15, 17, 16, 16, 17, 17, 17, 18,
+            15, 20, 12, 21, 8, 21, 5, 20, 3, 18, 3, 16, 4, 14, 5, 13, 7, 12, 13, 10, 15, 9, 17, 7,
+            -1, -1, 3, 16, 5, 14, 7, 13, 13, 11, 15, 10, 16, 9, 17, 7, 17, 3, 15, 1, 12, 0, 8, 0,
+            5, 1, 3, 3, 3, 4, 4, 5, 5, 4, 4, 3,
+        ],
+    ),
+    (
+        24,
+        &[
+            21, 21, 3, 0, -1, -1, 8, 21, 10, 19, 10, 17, 9, 15, 7, 14, 5, 14, 3, 16, 3, 18, 4, 20,
+            6, 21, 8, 21, 10, 20, 13, 19, 16, 19, 19, 20, 21, 21, -1, -1, 17, 7, 15, 6, 14, 4, 14,
+            2, 16, 0, 18, 0, 20, 1, 21, 3, 21, 5, 19, 7, 17, 7,
+        ],
+    ),
+    (
+        26,
+        &[
+            23, 12, 23, 13, 22, 14, 21, 14, 20, 13, 19, 11, 17, 6, 15, 3, 13, 1, 11, 0, 7, 0, 5, 1,
+            4, 2, 3, 4, 3, 6, 4, 8, 5, 9, 12, 13, 13, 14, 14, 16, 14, 18, 13, 20, 11, 21, 9, 20, 8,
+            18, 8, 16, 9, 13, 11, 10, 16, 3, 18, 1, 20, 0, 22, 0, 23, 1, 23, 2,
+        ],
+    ),
+    (10, &[5, 19, 4, 20, 5, 21, 6, 20, 6, 18, 5, 16, 4, 15]),
+    (
+        14,
+        &[
+            11, 25, 9, 23, 7, 20, 5, 16, 4, 11, 4, 7, 5, 2, 7, -2, 9, -5, 11, -7, -1, -1, 9, 23, 7,
+            19, 6, 16, 5, 11, 5, 7, 6, 2, 7, -1, 9, -5,
+        ],
+    ),
+    (
+        14,
+        &[
+            3, 25, 5, 23, 7, 20, 9, 16, 10, 11, 10, 7, 9, 2, 7, -2, 5, -5, 3, -7, -1, -1, 5, 23, 7,
+            19, 8, 16, 9, 11, 9, 7, 8, 2, 7, -1, 5, -5,
+        ],
+    ),
+    (
+        16,
+        &[8, 15, 8, 3, -1, -1, 3, 12, 13, 6, -1, -1, 13, 12, 3, 6],
+    ),
+    (26, &[13, 18, 13, 0, -1, -1, 4, 9, 22, 9]),
+    (10, &[6, 1, 5, 1, 4, 2, 5, 3, 6, 2, 6, 0, 5, -2, 4, -3]),
+    (26, &[4, 9, 22, 9]),
+    (10, &[5, 3, 4, 2, 5, 1, 6, 2, 5, 3]),
+    (22, &[20, 25, 2, -7]),
+    (
+        20,
+        &[
+            9, 21, 6, 20, 4, 17, 3, 12, 3, 9, 4, 4, 6, 1, 9, 0, 11, 0, 14, 1, 16, 4, 17, 9, 17, 12,
+            16, 17, 14, 20, 11, 21, 9, 21, -1, -1, 9, 21, 7, 20, 6, 19, 5, 17, 4, 12, 4, 9, 5, 4,
+            6, 2, 7, 1, 9, 0, -1, -1, 11, 0, 13, 1, 14, 2, 15, 4, 16, 9, 16, 12, 15, 17, 14, 19,
+            13, 20, 11, 21,
+        ],
+    ),
+    (
+        20,
+        &[
+            6, 17, 8, 18, 11, 21, 11, 1, -1, -1, 10, 20, 10, 1, -1, -1, 6, 1, 15, 1,
+        ],
+    ),
+    (
+        20,
+        &[
+            4, 17, 5, 16, 4, 15, 3, 16, 3, 17, 4, 19, 5, 20, 8, 21, 12, 21, 15, 20, 16, 19, 17, 17,
+            17, 15, 16, 13, 13, 11, 8, 9, 6, 8, 4, 6, 3, 3, 3, 1, -1, -1, 12, 21, 14, 20, 15, 19,
+            16, 17, 16, 15, 15, 13, 12, 11, 8, 9, -1, -1, 3, 3, 4, 4, 6, 4, 11, 2, 14, 2, 16, 3,
+            17, 4, -1, -1, 6, 4, 11, 1, 14, 1, 16, 2, 17, 4, 17, 6,
+        ],
+    ),
+    (
+        20,
+        &[
+            4, 17, 5, 16, 4, 15, 3, 16, 3, 17, 4, 19, 5, 20, 8, 21, 12, 21, 15, 20, 16, 18, 16, 15,
+            15, 13, 12, 12, 9, 12, -1, -1, 12, 21, 14, 20, 15, 18, 15, 15, 14, 13, 12, 12, -1, -1,
+            12, 12, 14, 11, 16, 9, 17, 7, 17, 4, 16, 2, 15, 1, 12, 0, 8, 0, 5, 1, 4, 2, 3, 4, 3, 5,
+            4, 6, 5, 5, 4, 4, -1, -1, 15, 10, 16, 7, 16, 4, 15, 2, 14, 1, 12, 0,
+        ],
+    ),
+    (
+        20,
+        &[
+            11, 18, 11, 1, -1, -1, 12, 21, 12, 1, -1, -1, 12, 21, 3, 6, 18, 6, -1, -1, 8, 1, 15, 1,
+        ],
+    ),
+    (
+        20,
+        &[
+            5, 21, 3, 11, -1, -1, 3, 11, 5, 13, 8, 14, 11, 14, 14, 13, 16, 11, 17, 8, 17, 6, 16, 3,
+            14, 1, 11, 0, 8, 0, 5, 1, 4, 2, 3, 4, 3, 5, 4, 6, 5, 5, 4, 4, -1, -1, 11, 14, 13, 13,
+            15, 11, 16, 8, 16, 6, 15, 3, 13, 1, 11, 0, -1, -1, 5, 21, 15, 21, -1, -1, 5, 20, 10,
+            20, 15, 21,
+        ],
+    ),
+    (
+        20,
+        &[
+            15, 18, 14, 17, 15, 16, 16, 17, 16, 18, 15, 20, 13, 21, 10, 21, 7, 20, 5, 18, 4, 16, 3,
+            12, 3, 6, 4, 3, 6, 1, 9, 0, 11, 0, 14, 1, 16, 3, 17, 6, 17, 7, 16, 10, 14, 12, 11, 13,
+            10, 13, 7, 12, 5, 10, 4, 7, -1, -1, 10, 21, 8, 20, 6, 18, 5, 16, 4, 12, 4, 6, 5, 3, 7,
+            1, 9, 0, -1, -1, 11, 0, 13, 1, 15, 3, 16, 6, 16, 7, 15, 10, 13, 12, 11, 13,
+        ],
+    ),
+    (
+        20,
+        &[
+            3, 21, 3, 15, -1, -1, 3, 17, 4, 19, 6, 21, 8, 21, 13, 18, 15, 18, 16, 19, 17, 21, -1,
+            -1, 4, 19, 6, 20, 8, 20, 13, 18, -1, -1, 17, 21, 17, 18, 16, 15, 12, 10, 11, 8, 10, 5,
+            10, 1, -1, -1, 16, 15, 11, 10, 10, 8, 9, 5, 9, 1,
+        ],
+    ),
+    (
+        20,
+        &[
+            8, 21, 5, 20, 4, 18, 4, 15, 5, 13, 8, 12, 12, 12, 15, 13, 16, 15, 16, 18, 15, 20, 12,
+            21, 8, 21, -1, -1, 8, 21, 6, 20, 5, 18, 5, 15, 6, 13, 8, 12, -1, -1, 12, 12, 14, 13,
+            15, 15, 15, 18, 14, 20, 12, 21, -1, -1, 8, 12, 5, 11, 4, 10, 3, 8, 3, 4, 4, 2, 5, 1, 8,
+            0, 12, 0, 15, 1, 16, 2, 17, 4, 17, 8, 16, 10, 15, 11, 12, 12, -1, -1, 8, 12, 6, 11, 5,
+            10, 4, 8, 4, 4, 5, 2, 6, 1, 8, 0, -1, -1, 12, 0, 14, 1, 15, 2, 16, 4, 16, 8, 15, 10,
+            14, 11, 12, 12,
+        ],
+    ),
+    (
+        20,
+        &[
+            16, 14, 15, 11, 13, 9, 10, 8, 9, 8, 6, 9, 4, 11, 3, 14, 3, 15, 4, 18, 6, 20, 9, 21, 11,
+            21, 14, 20, 16, 18, 17, 15, 17, 9, 16, 5, 15, 3, 13, 1, 10, 0, 7, 0, 5, 1, 4, 3, 4, 4,
+            5, 5, 6, 4, 5, 3, -1, -1, 9, 8, 7, 9, 5, 11, 4, 14, 4, 15, 5, 18, 7, 20, 9, 21, -1, -1,
+            11, 21, 13, 20, 15, 18, 16, 15, 16, 9, 15, 5, 14, 3, 12, 1, 10, 0,
+        ],
+    ),
+    (
+        10,
+        &[
+            5, 14, 4, 13, 5, 12, 6, 13, 5, 14, -1, -1, 5, 3, 4, 2, 5, 1, 6, 2, 5, 3,
+        ],
+    ),
+    (
+        10,
+        &[
+            5, 14, 4, 13, 5, 12, 6, 13, 5, 14, -1, -1, 6, 1, 5, 1, 4, 2, 5, 3, 6, 2, 6, 0, 5, -2,
+            4, -3,
+        ],
+    ),
+    (24, &[20, 18, 4, 9, 20, 0]),
+    (26, &[4, 12, 22, 12, -1, -1, 4, 6, 22, 6]),
+    (24, &[4, 18, 20, 9, 4, 0]),
+    (
+        18,
+        &[
+            3, 17, 4, 16, 3, 15, 2, 16, 2, 17, 3, 19, 4, 20, 7, 21, 11, 21, 14, 20, 15, 19, 16, 17,
+            16, 15, 15, 13, 14, 12, 10, 10, 10, 8, -1, -1, 11, 21, 13, 20, 14, 19, 15, 17, 15, 15,
+            14, 13, 13, 12, 11, 11, -1, -1, 10, 3, 9, 2, 10, 1, 11, 2, 10, 3,
+        ],
+    ),
+    (
+        27,
+        &[
+            18, 13, 17, 15, 15, 16, 12, 16, 10, 15, 9, 14, 8, 11, 8, 8, 9, 6, 11, 5, 14, 5, 16, 6,
+            17, 8, -1, -1, 12, 16, 10, 14, 9, 11, 9, 8, 10, 6, 11, 5, -1, -1, 18, 16, 17, 8, 17, 6,
+            19, 5, 21, 5, 23, 7, 24, 10, 24, 12, 23, 15, 22, 17, 20, 19, 18, 20, 15, 21, 12, 21, 9,
+            20, 7, 19, 5, 17, 4, 12, 4, 10, 5, 7, 7, 3, 9, 0, 12, -1, 15, -1, 18, 0, 20, 1, 21, 2,
+            -1, -1, 19, 16, 18, 8, 18, 6, 19, 5,
+        ],
+    ),
+    (
+        20,
+        &[
+            10, 21, 3, 1, -1, -1, 10, 21, 17, 1, -1, -1, 10, 18, 16, 1, -1, -1, 5, 7, 14, 7, -1,
+            -1, 1, 1, 7, 1, -1, -1, 13, 1, 19, 1,
+        ],
+    ),
+    (
+        22,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 2, 21, 14, 21, 17, 20, 18, 19, 19, 17, 19,
+            15, 18, 13, 17, 12, 14, 11, -1, -1, 14, 21, 16, 20, 17, 19, 18, 17, 18, 15, 17, 13, 16,
+            12, 14, 11, -1, -1, 6, 11, 14, 11, 17, 10, 18, 9, 19, 7, 19, 4, 18, 2, 17, 1, 14, 1, 2,
+            1, -1, -1, 14, 11, 16, 10, 17, 9, 18, 7, 18, 4, 17, 2, 16, 1, 14, 1,
+        ],
+    ),
+    (
+        21,
+        &[
+            17, 18, 18, 15, 18, 21, 17, 18, 15, 20, 12, 21, 10, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3,
+            8, 4, 5, 5, 3, 7, 1, 10, 0, 12, 0, 15, 1, 17, 3, 18, 5, -1, -1, 10, 21, 8, 20, 6, 18,
+            5, 16, 4, 13, 4, 8, 5, 5, 6, 3, 8, 1, 10, 0,
+        ],
+    ),
+    (
+        22,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 2, 21, 12, 21, 15, 20, 17, 18, 18, 16, 19,
+            13, 19, 8, 18, 5, 17, 3, 15, 1, 12, 1, 2, 1, -1, -1, 12, 21, 14, 20, 16, 18, 17, 16,
+            18, 13, 18, 8, 17, 5, 16, 3, 14, 1, 12, 1,
+        ],
+    ),
+    (
+        21,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 12, 15, 12, 7, -1, -1, 2, 21, 18, 21, 18, 15,
+            17, 21, -1, -1, 6, 11, 12, 11, -1, -1, 2, 1, 18, 1, 18, 7, 17, 1,
+        ],
+    ),
+    (
+        20,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 12, 15, 12, 7, -1, -1, 2, 21, 18, 21, 18, 15,
+            17, 21, -1, -1, 6, 11, 12, 11, -1, -1, 2, 1, 9, 1,
+        ],
+    ),
+    (
+        23,
+        &[
+            17, 18, 18, 15, 18, 21, 17, 18, 15, 20, 12, 21, 10, 21, 7, 20, 5, 18, 4, 16, 3, 13, 3,
+            8, 4, 5, 5, 3, 7, 1, 10, 0, 12, 0, 15, 1, 17, 3, -1, -1, 10, 21, 8, 20, 6, 18, 5, 16,
+            4, 13, 4, 8, 5, 5, 6, 3, 8, 1, 10, 0, -1, -1, 17, 8, 17, 1, -1, -1, 18, 8, 18, 1, -1,
+            -1, 14, 8, 21, 8,
+        ],
+    ),
+    (
+        24,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 18, 21, 18, 1, -1, -1, 19, 21, 19, 1, -1, -1,
+            2, 21, 9, 21, -1, -1, 15, 21, 22, 21, -1, -1, 6, 11, 18, 11, -1, -1, 2, 1, 9, 1, -1,
+            -1, 15, 1, 22, 1,
+        ],
+    ),
+    (
+        11,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 2, 21, 9, 21, -1, -1, 2, 1, 9, 1,
+        ],
+    ),
+    (
+        16,
+        &[
+            11, 21, 11, 5, 10, 2, 8, 1, 6, 1, 4, 2, 3, 4, 3, 6, 4, 7, 5, 6, 4, 5, -1, -1, 10, 21,
+            10, 5, 9, 2, 8, 1, -1, -1, 7, 21, 14, 21,
+        ],
+    ),
+    (
+        22,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 19, 21, 6, 8, -1, -1, 11, 12, 19, 1, -1, -1,
+            10, 12, 18, 1, -1, -1, 2, 21, 9, 21, -1, -1, 15, 21, 21, 21, -1, -1, 2, 1, 9, 1, -1,
+            -1, 15, 1, 21, 1,
+        ],
+    ),
+    (
+        18,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 2, 21, 9, 21, -1, -1, 2, 1, 17, 1, 17, 7, 16,
+            1,
+        ],
+    ),
+    (
+        25,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 12, 4, -1, -1, 5, 21, 12, 1, -1, -1, 19, 21, 12, 1, -1, -1,
+            19, 21, 19, 1, -1, -1, 20, 21, 20, 1, -1, -1, 2, 21, 6, 21, -1, -1, 19, 21, 23, 21, -1,
+            -1, 2, 1, 8, 1, -1, -1, 16, 1, 23, 1,
+        ],
+    ),
+    (
+        23,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 18, 3, -1, -1, 6, 21, 18, 1, -1, -1, 18, 21, 18, 1, -1, -1,
+            2, 21, 6, 21, -1, -1, 15, 21, 21, 21, -1, -1, 2, 1, 8, 1,
+        ],
+    ),
+    (
+        22,
+        &[
+            10, 21, 7, 20, 5, 18, 4, 16, 3, 12, 3, 9, 4, 5, 5, 3, 7, 1, 10, 0, 12, 0, 15, 1, 17, 3,
+            18, 5, 19, 9, 19, 12, 18, 16, 17, 18, 15, 20, 12, 21, 10, 21, -1, -1, 10, 21, 8, 20, 6,
+            18, 5, 16, 4, 12, 4, 9, 5, 5, 6, 3, 8, 1, 10, 0, -1, -1, 12, 0, 14, 1, 16, 3, 17, 5,
+            18, 9, 18, 12, 17, 16, 16, 18, 14, 20, 12, 21,
+        ],
+    ),
+    (
+        22,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 2, 21, 14, 21, 17, 20, 18, 19, 19, 17, 19,
+            14, 18, 12, 17, 11, 14, 10, 6, 10, -1, -1, 14, 21, 16, 20, 17, 19, 18, 17, 18, 14, 17,
+            12, 16, 11, 14, 10, -1, -1, 2, 1, 9, 1,
+        ],
+    ),
+    (
+        22,
+        &[
+            10, 21, 7, 20, 5, 18, 4, 16, 3, 12, 3, 9, 4, 5, 5, 3, 7, 1, 10, 0, 12, 0, 15, 1, 17, 3,
+            18, 5, 19, 9, 19, 12, 18, 16, 17, 18, 15, 20, 12, 21, 10, 21, -1, -1, 10, 21, 8, 20, 6,
+            18, 5, 16, 4, 12, 4, 9, 5, 5, 6, 3, 8, 1, 10, 0, -1, -1, 12, 0, 14, 1, 16, 3, 17, 5,
+            18, 9, 18, 12, 17, 16, 16, 18, 14, 20, 12, 21, -1, -1, 7, 2, 7, 3, 8, 5, 10, 6, 11, 6,
+            13, 5, 14, 3, 15, -4, 16, -5, 18, -5, 19, -3, 19, -2, -1, -1, 14, 3, 15, -1, 16, -3,
+            17, -4, 18, -4, 19, -3,
+        ],
+    ),
+    (
+        22,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 2, 21, 14, 21, 17, 20, 18, 19, 19, 17, 19,
+            15, 18, 13, 17, 12, 14, 11, 6, 11, -1, -1, 14, 21, 16, 20, 17, 19, 18, 17, 18, 15, 17,
+            13, 16, 12, 14, 11, -1, -1, 2, 1, 9, 1, -1, -1, 11, 11, 13, 10, 14, 9, 17, 2, 18, 1,
+            19, 1, 20, 2, -1, -1, 13, 10, 14, 8, 16, 1, 17, 0, 19, 0, 20, 2, 20, 3,
+        ],
+    ),
+    (
+        20,
+        &[
+            16, 18, 17, 21, 17, 15, 16, 18, 14, 20, 11, 21, 8, 21, 5, 20, 3, 18, 3, 16, 4, 14, 5,
+            13, 7, 12, 13, 10, 15, 9, 17, 7, -1, -1, 3, 16, 5, 14, 7, 13, 13, 11, 15, 10, 16, 9,
+            17, 7, 17, 3, 15, 1, 12, 0, 9, 0, 6, 1, 4, 3, 3, 6, 3, 0, 4, 3,
+        ],
+    ),
+    (
+        19,
+        &[
+            9, 21, 9, 1, -1, -1, 10, 21, 10, 1, -1, -1, 3, 21, 2, 15, 2, 21, 17, 21, 17, 15, 16,
+            21, -1, -1, 6, 1, 13, 1,
+        ],
+    ),
+    (
+        24,
+        &[
+            5, 21, 5, 6, 6, 3, 8, 1, 11, 0, 13, 0, 16, 1, 18, 3, 19, 6, 19, 21, -1, -1, 6, 21, 6,
+            6, 7, 3, 9, 1, 11, 0, -1, -1, 2, 21, 9, 21, -1, -1, 16, 21, 22, 21,
+        ],
+    ),
+    (
+        20,
+        &[
+            3, 21, 10, 1, -1, -1, 4, 21, 10, 3, -1, -1, 17, 21, 10, 1, -1, -1, 1, 21, 7, 21, -1,
+            -1, 13, 21, 19, 21,
+        ],
+    ),
+    (
+        24,
+        &[
+            4, 21, 8, 1, -1, -1, 5, 21, 8, 6, -1, -1, 12, 21, 8, 1, -1, -1, 12, 21, 16, 1, -1, -1,
+            13, 21, 16, 6, -1, -1, 20, 21, 16, 1, -1, -1, 1, 21, 8, 21, -1, -1, 17, 21, 23, 21,
+        ],
+    ),
+    (
+        20,
+        &[
+            3, 21, 16, 1, -1, -1, 4, 21, 17, 1, -1, -1, 17, 21, 3, 1, -1, -1, 1, 21, 7, 21, -1, -1,
+            13, 21, 19, 21, -1, -1, 1, 1, 7, 1, -1, -1, 13, 1, 19, 1,
+        ],
+    ),
+    (
+        21,
+        &[
+            3, 21, 10, 10, 10, 1, -1, -1, 4, 21, 11, 10, 11, 1, -1, -1, 18, 21, 11, 10, -1, -1, 1,
+            21, 7, 21, -1, -1, 14, 21, 20, 21, -1, -1, 7, 1, 14, 1,
+        ],
+    ),
+    (
+        20,
+        &[
+            16, 21, 3, 1, -1, -1, 17, 21, 4, 1, -1, -1, 4, 21, 3, 15, 3, 21, 17, 21, -1, -1, 3, 1,
+            17, 1, 17, 7, 16, 1,
+        ],
+    ),
+    (
+        14,
+        &[
+            4, 25, 4, -7, -1, -1, 5, 25, 5, -7, -1, -1, 4, 25, 11, 25, -1, -1, 4, -7, 11, -7,
+        ],
+    ),
+    (14, &[0, 21, 14, -3]),
+    (
+        14,
+        &[
+            9, 25, 9, -7, -1, -1, 10, 25, 10, -7, -1, -1, 3, 25, 10, 25, -1, -1, 3, -7, 10, -7,
+        ],
+    ),
+    (16, &[8, 23, 0, 9, -1, -1, 8, 23, 16, 9]),
+    (18, &[0, -7, 18, -7]),
+    (10, &[6, 21, 5, 20, 4, 18, 4, 16, 5, 15, 6, 16, 5, 17]),
+    (
+        19,
+        &[
+            5, 12, 5, 11, 4, 11, 4, 12, 5, 13, 7, 14, 11, 14, 13, 13, 14, 12, 15, 10, 15, 1, 16, 1,
+            -1, -1, 14, 12, 14, 2, 15, 1, 17, 1, 18, 2, -1, -1, 14, 10, 13, 9, 7, 8, 4, 7, 3, 5, 3,
+            3, 4, 1, 7, 0, 10, 0, 12, 1, 14, 3, -1, -1, 7, 8, 5, 7, 4, 5, 4, 3, 5, 1, 7, 0,
+        ],
+    ),
+    (
+        19,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 6, 11, 8, 13, 10, 14, 12, 14, 15, 13, 17, 11,
+            18, 8, 18, 6, 17, 3, 15, 1, 12, 0, 10, 0, 8, 1, 6, 3, -1, -1, 12, 14, 14, 13, 16, 11,
+            17, 8, 17, 6, 16, 3, 14, 1, 12, 0, -1, -1, 2, 21, 6, 21,
+        ],
+    ),
+    (
+        18,
+        &[
+            15, 11, 14, 10, 15, 9, 16, 10, 16, 11, 14, 13, 12, 14, 9, 14, 6, 13, 4, 11, 3, 8, 3, 6,
+            4, 3, 6, 1, 9, 0, 11, 0, 14, 1, 16, 3, -1, -1, 9, 14, 7, 13, 5, 11, 4, 8, 4, 6, 5, 3,
+            7, 1, 9, 0,
+        ],
+    ),
+    (
+        19,
+        &[
+            14, 21, 14, 1, -1, -1, 15, 21, 15, 1, -1, -1, 14, 11, 12, 13, 10, 14, 8, 14, 5, 13, 3,
+            11, 2, 8, 2, 6, 3, 3, 5, 1, 8, 0, 10, 0, 12, 1, 14, 3, -1, -1, 8, 14, 6, 13, 4, 11, 3,
+            8, 3, 6, 4, 3, 6, 1, 8, 0, -1, -1, 11, 21, 15, 21, -1, -1, 14, 1, 18, 1,
+        ],
+    ),
+    (
+        18,
+        &[
+            3, 8, 15, 8, 15, 10, 14, 12, 13, 13, 11, 14, 8, 14, 5, 13, 3, 11, 2, 8, 2, 6, 3, 3, 5,
+            1, 8, 0, 10, 0, 13, 1, 15, 3, -1, -1, 14, 8, 14, 11, 13, 13, -1, -1, 8, 14, 6, 13, 4,
+            11, 3, 8, 3, 6, 4, 3, 6, 1, 8, 0,
+        ],
+    ),
+    (
+        14,
+        &[
+            11, 20, 10, 19, 11, 18, 12, 19, 12, 20, 11, 21, 9, 21, 7, 20, 6, 18, 6, 1, -1, -1, 9,
+            21, 8, 20, 7, 18, 7, 1, -1, -1, 3, 14, 11, 14, -1, -1, 3, 1, 10, 1,
+        ],
+    ),
+    (
+        19,
+        &[
+            8, 14, 6, 13, 5, 12, 4, 10, 4, 8, 5, 6, 6, 5, 8, 4, 10, 4, 12, 5, 13, 6, 14, 8, 14, 10,
+            13, 12, 12, 13, 10, 14, 8, 14, -1, -1, 6, 13, 5, 11, 5, 7, 6, 5, -1, -1, 12, 5, 13, 7,
+            13, 11, 12, 13, -1, -1, 13, 12, 14, 13, 16, 14, 16, 13, 14, 13, -1, -1, 5, 6, 4, 5, 3,
+            3, 3, 2, 4, 0, 7, -1, 12, -1, 15, -2, 16, -3, -1, -1, 3, 2, 4, 1, 7, 0, 12, 0, 15, -1,
+            16, -3, 16, -4, 15, -6, 12, -7, 6, -7, 3, -6, 2, -4, 2, -3, 3, -1, 6, 0,
+        ],
+    ),
+    (
+        22,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 6, 11, 8, 13, 11, 14, 13, 14, 16, 13, 17, 11,
+            17, 1, -1, -1, 13, 14, 15, 13, 16, 11, 16, 1, -1, -1, 2, 21, 6, 21, -1, -1, 2, 1, 9, 1,
+            -1, -1, 13, 1, 20, 1,
+        ],
+    ),
+    (
+        11,
+        &[
+            5, 21, 4, 20, 5, 19, 6, 20, 5, 21, -1, -1, 5, 14, 5, 1, -1, -1, 6, 14, 6, 1, -1, -1, 2,
+            14, 6, 14, -1, -1, 2, 1, 9, 1,
+        ],
+    ),
+    (
+        11,
+        &[
+            6, 21, 5, 20, 6, 19, 7, 20, 6, 21, -1, -1, 7, 14, 7, -3, 6, -5, 4, -6, 2, -6, 1, -5, 1,
+            -4, 2, -3, 3, -4, 2, -5, -1, -1, 6, 14, 6, -3, 5, -5, 4, -6, -1, -1, 3, 14, 7, 14,
+        ],
+    ),
+    (
+        21,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 16, 14, 6, 4, -1, -1, 11, 8, 17, 1, -1, -1,
+            10, 8, 16, 1, -1, -1, 2, 21, 6, 21, -1, -1, 13, 14, 19, 14, -1, -1, 2, 1, 9, 1, -1, -1,
+            13, 1, 19, 1,
+        ],
+    ),
+    (
+        11,
+        &[
+            5, 21, 5, 1, -1, -1, 6, 21, 6, 1, -1, -1, 2, 21, 6, 21, -1, -1, 2, 1, 9, 1,
+        ],
+    ),
+    (
+        33,
+        &[
+            5, 14, 5, 1, -1, -1, 6, 14, 6, 1, -1, -1, 6, 11, 8, 13, 11, 14, 13, 14, 16, 13, 17, 11,
+            17, 1, -1, -1, 13, 14, 15, 13, 16, 11, 16, 1, -1, -1, 17, 11, 19, 13, 22, 14, 24, 14,
+            27, 13, 28, 11, 28, 1, -1, -1, 24, 14, 26, 13, 27, 11, 27, 1, -1, -1, 2, 14, 6, 14, -1,
+            -1, 2, 1, 9, 1, -1, -1, 14, 1, 20, 1, -1, -1, 25, 1, 31, 1,
+        ],
+    ),
+    (
+        22,
+        &[
+            5, 14, 5, 1, -1, -1, 6, 14, 6, 1, -1, -1, 6, 11, 8, 13, 11, 14, 13, 14, 16, 13, 17, 11,
+            17, 1, -1, -1, 13, 14, 15, 13, 16, 11, 16, 1, -1, -1, 2, 14, 6, 14, -1, -1, 2, 1, 9, 1,
+            -1, -1, 13, 1, 20, 1,
+        ],
+    ),
+    (
+        20,
+        &[
+            9, 14, 6, 13, 4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 9, 0, 11, 0, 14, 1, 16, 3, 17, 6, 17, 8,
+            16, 11, 14, 13, 11, 14, 9, 14, -1, -1, 9, 14, 7, 13, 5, 11, 4, 8, 4, 6, 5, 3, 7, 1, 9,
+            0, -1, -1, 11, 0, 13, 1, 15, 3, 16, 6, 16, 8, 15, 11, 13, 13, 11, 14,
+        ],
+    ),
+    (
+        21,
+        &[
+            5, 14, 5, -6, -1, -1, 6, 14, 6, -6, -1, -1, 6, 11, 8, 13, 10, 14, 12, 14, 15, 13, 17,
+            11, 18, 8, 18, 6, 17, 3, 15, 1, 12, 0, 10, 0, 8, 1, 6, 3, -1, -1, 12, 14, 14, 13, 16,
+            11, 17, 8, 17, 6, 16, 3, 14, 1, 12, 0, -1, -1, 2, 14, 6, 14, -1, -1, 2, -6, 9, -6,
+        ],
+    ),
+    (
+        20,
+        &[
+            15, 14, 15, -6, -1, -1, 16, 14, 16, -6, -1, -1, 15, 11, 13, 13, 11, 14, 9, 14, 6, 13,
+            4, 11, 3, 8, 3, 6, 4, 3, 6, 1, 9, 0, 11, 0, 13, 1, 15, 3, -1, -1, 9, 14, 7, 13, 5, 11,
+            4, 8, 4, 6, 5, 3, 7, 1, 9, 0, -1, -1, 12, -6, 19, -6,
+        ],
+    ),
+    (
+        17,
+        &[
+            5, 14, 5, 1, -1, -1, 6, 14, 6, 1, -1, -1, 6, 8, 7, 11, 9, 13, 11, 14, 14, 14, 15, 13,
+            15, 12, 14, 11, 13, 12, 14, 13, -1, -1, 2, 14, 6, 14, -1, -1, 2, 1, 9, 1,
+        ],
+    ),
+    (
+        17,
+        &[
+            13, 12, 14, 14, 14, 10, 13, 12, 12, 13, 10, 14, 6, 14, 4, 13, 3, 12, 3, 10, 4, 9, 6, 8,
+            11, 6, 13, 5, 14, 4, -1, -1, 3, 11, 4, 10, 6, 9, 11, 7, 13, 6, 14, 5, 14, 2, 13, 1, 11,
+            0, 7, 0, 5, 1, 4, 2, 3, 4, 3, 0, 4, 2,
+        ],
+    ),
+    (
+        15,
+        &[
+            5, 18, 5, 5, 6, 2, 7, 1, 9, 0, 11, 0, 13, 1, 14, 3, -1, -1, 6, 18, 6, 5, 7, 2, 8, 1,
+            -1, -1, 2, 14, 11, 14,
+        ],
+    ),
+    (
+        22,
+        &[
+            5, 14, 5, 4, 6, 2, 7, 1, 9, 0, 12, 0, 14, 1, 16, 3, -1, -1, 6, 14, 6, 4, 7, 2, 8, 1, 9,
+            0, -1, -1, 16, 14, 16, 1, -1, -1, 17, 14, 17, 1, -1, -1, 2, 14, 6, 14, -1, -1, 13, 14,
+            17, 14, -1, -1, 16, 1, 20, 1,
+        ],
+    ),
+    (
+        18,
+        &[
+            3, 14, 9, 1, -1, -1, 4, 14, 9, 3, -1, -1, 15, 14, 9, 1, -1, -1, 1, 14, 7, 14, -1, -1,
+            11, 14, 17, 14,
+        ],
+    ),
+    (
+        24,
+        &[
+            4, 14, 8, 1, -1, -1, 5, 14, 8, 4, -1, -1, 12, 14, 8, 1, -1, -1, 12, 14, 16, 1, -1, -1,
+            13, 14, 16, 4, -1, -1, 20, 14, 16, 1, -1, -1, 1, 14, 8, 14, -1, -1, 17, 14, 23, 14,
+        ],
+    ),
+    (
+        20,
+        &[
+            4, 14, 15, 1, -1, -1, 5, 14, 16, 1, -1, -1, 16, 14, 4, 1, -1, -1, 2, 14, 8, 14, -1, -1,
+            12, 14, 18, 14, -1, -1, 2, 1, 8, 1, -1, -1, 12, 1, 18, 1,
+        ],
+    ),
+    (
+        19,
+        &[
+            4, 14, 10, 1, -1, -1, 5, 14, 10, 3, -1, -1, 16, 14, 10, 1, 8, -3, 6, -5, 4, -6, 2, -6,
+            1, -5, 1, -4, 2, -3, 3, -4, 2, -5, -1, -1, 2, 14, 8, 14, -1, -1, 12, 14, 18, 14,
+        ],
+    ),
+    (
+        18,
+        &[
+            14, 14, 3, 1, -1, -1, 15, 14, 4, 1, -1, -1, 4, 14, 3, 10, 3, 14, 15, 14, -1, -1, 3, 1,
+            15, 1, 15, 5, 14, 1,
+        ],
+    ),
+    (
+        14,
+        &[
+            9, 25, 7, 24, 6, 23, 5, 21, 5, 19, 6, 17, 7, 16, 8, 14, 8, 12, 6, 10, -1, -1, 7, 24, 6,
+            22, 6, 20, 7, 18, 8, 17, 9, 15, 9, 13, 8, 11, 4, 9, 8, 7, 9, 5, 9, 3, 8, 1, 7, 0, 6,
+            -2, 6, -4, 7, -6, -1, -1, 6, 8, 8, 6, 8, 4, 7, 2, 6, 1, 5, -1, 5, -3, 6, -5, 7, -6, 9,
+            -7,
+        ],
+    ),
+    (8, &[4, 25, 4, -7]),
+    (
+        14,
+        &[
+            5, 25, 7, 24, 8, 23, 9, 21, 9, 19, 8, 17, 7, 16, 6, 14, 6, 12, 8, 10, -1, -1, 7, 24, 8,
+            22, 8, 20, 7, 18, 6, 17, 5, 15, 5, 13, 6, 11, 10, 9, 6, 7, 5, 5, 5, 3, 6, 1, 7, 0, 8,
+            -2, 8, -4, 7, -6, -1, -1, 8, 8, 6, 6, 6, 4, 7, 2, 8, 1, 9, -1, 9, -3, 8, -5, 7, -6, 5,
+            -7,
+        ],
+    ),
+    (
+        24,
+        &[
+            3, 6, 3, 8, 4, 11, 6, 12, 8, 12, 10, 11, 14, 8, 16, 7, 18, 7, 20, 8, 21, 10, -1, -1, 3,
+            8, 4, 10, 6, 11, 8, 11, 10, 10, 14, 7, 16, 6, 18, 6, 20, 7, 21, 10, 21, 12,
+        ],
+    ),
+];
+
+// Hershey Script Simplex, in the same form as SIMPLEX
+const SCRIPT: [(i8, &[i8]); 95] = [
+    (16, &[]),
+    (10, &[10, 21, 7, 7, -1, -1, 5, 2, 4, 1, 5, 0, 6, 1, 5, 2]),
+    (16, &[9, 21, 7, 14, -1, -1, 17, 21, 15, 14]),
+    (
+        21,
+        &[
+            16, 25, 2, -7, -1, -1, 22, 25, 8, -7, -1, -1, 7, 12, 21, 12, -1, -1, 4, 6, 18, 6,
+        ],
+    ),
+    (
+        20,
+        &[
+            14, 25, 7, -4, -1, -1, 18, 25, 11, -4, -1, -1, 21, 18, 19, 20, 17, 21, 13, 21, 9, 20,
+            7, 18, 7, 14, 8, 13, 10, 12, 15, 10, 17, 9, 18, 8, 18, 3, 15, 1, 12, 0, 8, 0, 5, 1, 4,
+            3,
+        ],
+    ),
+    (
+        24,
+        &[
+            26, 21, 3, 0, -1, -1, 13, 21, 14, 19, 14, 17, 12, 15, 10, 14, 8, 14, 7, 16, 7, 18, 8,
+            20, 11, 21, 13, 21, 14, 20, 17, 19, 20, 19, 26, 21, -1, -1, 19, 7, 16, 6, 14, 2, 16, 0,
+            18, 0, 20, 1, 22, 3, 22, 5, 21, 7, 19, 7,
+        ],
+    ),
+    (
+        26,
+        &[
+            26, 12, 26, 13, 25, 14, 24, 14, 21, 11, 18, 6, 16, 3, 13, 1, 11, 0, 7, 0, 5, 1, 4, 2,
+            4, 6, 7, 9, 15, 13, 18, 16, 18, 18, 17, 20, 16, 21, 13, 20, 12, 18, 12, 13, 13, 10, 17,
+            3, 18, 1, 20, 0, 22, 0, 23, 1, 23, 2,
+        ],
+    ),
+    (10, &[9, 19, 8, 20, 10, 21, 10, 18, 9, 16, 7, 15]),
+    (
+        14,
+        &[
+            16, 25, 11, 20, 9, 16, 6, 11, 6, 7, 5, 2, 7, -2, 8, -5, 9, -7,
+        ],
+    ),
+    (
+        14,
+        &[
+            8, 25, 10, 23, 11, 20, 13, 16, 12, 11, 12, 7, 9, 2, 7, -2, 4, -5, 1, -7,
+        ],
+    ),
+    (
+        16,
+        &[13, 21, 10, 9, -1, -1, 7, 18, 16, 12, -1, -1, 17, 18, 6, 12],
+    ),
+    (26, &[17, 18, 13, 0, -1, -1, 6, 9, 24, 9]),
+    (10, &[6, 1, 5, 0, 4, 1, 5, 2, 6, 1, 6, -1, 3, -4]),
+    (26, &[6, 9, 24, 9]),
+    (10, &[5, 2, 4, 1, 5, 0, 6, 1, 5, 2]),
+    (22, &[26, 25, 0, -7]),
+    (
+        20,
+        &[
+            14, 21, 10, 20, 8, 17, 6, 12, 5, 9, 5, 4, 6, 1, 9, 0, 11, 0, 14, 1, 17, 4, 19, 9, 20,
+            12, 20, 17, 18, 20, 16, 21, 14, 21,
+        ],
+    ),
+    (20, &[10, 17, 12, 18, 16, 21, 11, 0]),
+    (
+        20,
+        &[
+            8, 16, 8, 17, 9, 19, 10, 20, 13, 21, 17, 21, 19, 19, 20, 17, 18, 13, 15, 10, 3, 0, 17,
+            0,
+        ],
+    ),
+    (
+        20,
+        &[
+            10, 21, 21, 21, 13, 13, 16, 13, 18, 12, 18, 11, 19, 8, 18, 6, 17, 3, 14, 1, 11, 0, 8,
+            0, 5, 1, 4, 2, 4, 4,
+        ],
+    ),
+    (20, &[18, 21, 5, 7, 20, 7, -1, -1, 18, 21, 13, 0]),
+    (
+        20,
+        &[
+            20, 21, 10, 21, 7, 12, 8, 13, 11, 14, 14, 14, 17, 13, 18, 11, 19, 8, 18, 6, 17, 3, 14,
+            1, 11, 0, 8, 0, 5, 1, 4, 2, 4, 4,
+        ],
+    ),
+    (
+        20,
+        &[
+            20, 18, 19, 20, 17, 21, 15, 21, 11, 20, 9, 17, 7, 12, 6, 7, 6, 3, 7, 1, 10, 0, 11, 0,
+            14, 1, 17, 3, 18, 6, 19, 7, 18, 10, 17, 12, 14, 13, 13, 13, 10, 12, 7, 10, 6, 7,
+        ],
+    ),
+    (20, &[22, 21, 7, 0, -1, -1, 8, 21, 22, 21]),
+    (
+        20,
+        &[
+            13, 21, 9, 20, 8, 18, 8, 14, 10, 13, 14, 12, 16, 11, 18, 9, 19, 7, 18, 4, 15, 1, 12, 0,
+            8, 0, 5, 1, 4, 2, 4, 4, 5, 7, 6, 9, 8, 11, 16, 13, 18, 14, 20, 16, 20, 18, 19, 20, 17,
+            21, 13, 21,
+        ],
+    ),
+    (
+        20,
+        &[
+            19, 14, 17, 11, 15, 9, 12, 8, 11, 8, 8, 9, 6, 11, 6, 15, 8, 18, 10, 20, 14, 21, 15, 21,
+            17, 20, 19, 18, 19, 14, 18, 9, 16, 4, 13, 1, 10, 0, 8, 0, 5, 1, 5, 3,
+        ],
+    ),
+    (
+        10,
+        &[
+            8, 14, 7, 13, 8, 12, 9, 13, 8, 14, -1, -1, 5, 2, 4, 1, 5, 0, 6, 1, 5, 2,
+        ],
+    ),
+    (
+        10,
+        &[
+            8, 14, 7, 13, 8, 12, 9, 13, 8, 14, -1, -1, 6, 1, 5, 0, 4, 1, 5, 2, 6, 1, 6, -1, 3, -4,
+        ],
+    ),
+    (24, &[24, 18, 6, 9, 20, 0]),
+    (26, &[7, 12, 25, 12, -1, -1, 5, 6, 23, 6]),
+    (24, &[8, 18, 22, 9, 4, 0]),
+    (
+        18,
+        &[
+            7, 16, 7, 17, 8, 19, 9, 20, 12, 21, 16, 21, 18, 19, 19, 17, 17, 13, 16, 12, 11, 10, 11,
+            7, -1, -1, 9, 2, 8, 1, 9, 0, 10, 1, 9, 2,
+        ],
+    ),
+    (
+        27,
+        &[
+            21, 13, 20, 15, 19, 16, 16, 16, 13, 15, 12, 14, 10, 11, 10, 6, 12, 5, 15, 5, 17, 6, 19,
+            8, -1, -1, 16, 16, 13, 14, 11, 11, 11, 6, 12, 5, -1, -1, 22, 16, 19, 8, 18, 6, 20, 5,
+            22, 5, 25, 7, 26, 10, 27, 12, 26, 15, 26, 17, 24, 19, 20, 21, 17, 21, 13, 20, 11, 19,
+            7, 15, 5, 9, 5, 6, 7, 2, 9, 1, 12, 0, 15, 0, 18, 1, 22, 3, -1, -1, 23, 16, 20, 8, 19,
+            6, 20, 5,
+        ],
+    ),
+    (
+        16,
+        &[
+            2, 0, 4, 2, 5, 4, 7, 6, 8, 8, 9, 9, 10, 11, 12, 12, 14, 16, 19, 21, 13, 0, 15, 1, 16,
+            2, -1, -1, 8, 7, 15, 7,
+        ],
+    ),
+    (
+        16,
+        &[
+            11, 21, 4, 0, -1, -1, 6, 17, 7, 18, 9, 19, 10, 20, 12, 21, 16, 21, 18, 20, 18, 19, 19,
+            18, 19, 17, 15, 13, 13, 12, 12, 11, 13, 11, 15, 10, 16, 10, 17, 9, 17, 4, 14, 1, 13, 1,
+            11, 0, 4, 0, 2, 1, 1, 1,
+        ],
+    ),
+    (
+        15,
+        &[
+            19, 17, 19, 19, 17, 21, 15, 21, 14, 20, 12, 20, 10, 18, 9, 16, 8, 15, 7, 13, 6, 12, 5,
+            10, 5, 8, 4, 6, 5, 4, 5, 3, 6, 2, 6, 1, 8, 0, 11, 0, 12, 1, 14, 2, 16, 4,
+        ],
+    ),
+    (
+        17,
+        &[
+            12, 21, 5, 0, -1, -1, 1, 1, 3, 0, 10, 0, 11, 1, 13, 2, 17, 6, 18, 8, 19, 9, 19, 11, 20,
+            12, 20, 17, 19, 18, 19, 19, 18, 20, 17, 20, 15, 21, 12, 21, 8, 19, 6, 17,
+        ],
+    ),
+    (
+        14,
+        &[
+            18, 18, 18, 20, 16, 21, 13, 21, 11, 20, 10, 19, 10, 14, 11, 12, 12, 12, 13, 11, 10, 11,
+            8, 10, 5, 7, 4, 5, 4, 2, 6, 0, 10, 0, 12, 1, 13, 2, 15, 3,
+        ],
+    ),
+    (
+        15,
+        &[
+            8, 19, 9, 20, 12, 20, 14, 21, 22, 21, -1, -1, 16, 21, 9, 3, 6, 0, 4, 0, 2, 2, -1, -1,
+            9, 11, 15, 11,
+        ],
+    ),
+    (
+        17,
+        &[
+            19, 17, 19, 19, 17, 21, 15, 21, 14, 20, 12, 20, 10, 18, 9, 16, 8, 15, 7, 13, 6, 12, 5,
+            10, 5, 8, 4, 6, 5, 4, 5, 3, 6, 2, 6, 1, 8, 0, 11, 0, 12, 1, 14, 2, 15, 3, 16, 5, 18,
+            10, -1, -1, 14, 10, 20, 10,
+        ],
+    ),
+    (
+        17,
+        &[
+            11, 21, 3, 0, -1, -1, 21, 21, 13, 0, 15, 1, 16, 2, -1, -1, 7, 10, 17, 10,
+        ],
+    ),
+    (
+        10,
+        &[
+            14, 21, 7, 3, 6, 1, 4, 0, 3, 0, 2, 1, 2, 2, -1, -1, 10, 21, 18, 21,
+        ],
+    ),
+    (
+        12,
+        &[
+            16, 21, 7, -3, 5, -7, 4, -8, 2, -8, 1, -7, 1, -6, 2, -4, -1, -1, 12, 21, 20, 21,
+        ],
+    ),
+    (
+        16,
+        &[
+            11, 21, 3, 0, -1, -1, 21, 21, 7, 9, -1, -1, 10, 11, 11, 9, 11, 5, 12, 3, 12, 1, 13, 0,
+            14, 0, 16, 1, 17, 2,
+        ],
+    ),
+    (
+        14,
+        &[
+            17, 19, 17, 21, 14, 21, 13, 20, 11, 16, 6, 3, 5, 1, 3, 0, 2, 0, 1, 1, 3, 2, 4, 1, 8, 1,
+            10, 0, 13, 0, 15, 2,
+        ],
+    ),
+    (
+        18,
+        &[3, 0, 12, 21, 11, 4, 22, 21, 15, 2, 14, 1, 16, 0, 18, 2],
+    ),
+    (17, &[3, 0, 12, 21, 13, 0, 22, 21]),
+    (
+        17,
+        &[
+            16, 21, 14, 21, 13, 20, 12, 20, 10, 19, 9, 17, 7, 15, 7, 13, 6, 11, 5, 10, 5, 8, 4, 6,
+            4, 4, 5, 3, 5, 2, 7, 0, 10, 0, 11, 1, 12, 1, 14, 2, 15, 4, 17, 6, 19, 10, 19, 11, 20,
+            13, 20, 18, 17, 21, 14, 21, 13, 19, 12, 18,
+        ],
+    ),
+    (
+        15,
+        &[
+            11, 21, 3, 0, -1, -1, 6, 17, 7, 18, 9, 19, 10, 20, 12, 21, 16, 21, 18, 20, 19, 19, 19,
+            15, 17, 13, 15, 12, 14, 11, 12, 11, 10, 10, 8, 12,
+        ],
+    ),
+    (
+        17,
+        &[
+            16, 21, 14, 21, 13, 20, 12, 20, 10, 19, 9, 17, 7, 15, 7, 13, 6, 11, 5, 10, 5, 8, 4, 6,
+            4, 4, 5, 3, 5, 2, 7, 0, 10, 0, 11, 1, 12, 1, 14, 2, 15, 4, 17, 6, 19, 10, 19, 11, 20,
+            13, 20, 18, 17, 21, 14, 21, 13, 19, 12, 18, -1, -1, 8, 3, 11, 0, 12, 0, 14, -1, 16, -1,
+        ],
+    ),
+    (
+        16,
+        &[
+            11, 21, 3, 0, -1, -1, 6, 17, 7, 18, 9, 19, 10, 20, 12, 21, 16, 21, 18, 20, 19, 19, 19,
+            15, 17, 13, 15, 12, 14, 11, 12, 11, 10, 10, 8, 12, -1, -1, 11, 10, 11, 9, 12, 7, 12, 1,
+            13, 0, 14, 0, 16, 1, 17, 2,
+        ],
+    ),
+    (
+        15,
+        &[
+            20, 18, 20, 20, 18, 21, 15, 21, 13, 20, 11, 18, 11, 13, 14, 10, 14, 4, 13, 3, 11, 2, 9,
+            0, 5, 0, 3, 2, 3, 4,
+        ],
+    ),
+    (
+        15,
+        &[
+            8, 19, 9, 20, 12, 20, 14, 21, 22, 21, -1, -1, 16, 21, 9, 3, 6, 0, 4, 0, 2, 2,
+        ],
+    ),
+    (
+        18,
+        &[
+            8, 19, 10, 21, 12, 21, 12, 19, 11, 17, 7, 6, 6, 4, 6, 2, 8, 0, 10, 0, 12, 1, 16, 5, 17,
+            7, 22, 21, 15, 2, 14, 1, 16, 0, 18, 2,
+        ],
+    ),
+    (17, &[10, 21, 8, 0, 23, 21]),
+    (20, &[9, 21, 6, 0, 14, 14, 13, 0, 25, 21]),
+    (17, &[9, 21, 15, 0, -1, -1, 22, 21, 2, 0]),
+    (
+        17,
+        &[
+            8, 19, 10, 21, 12, 21, 12, 19, 11, 17, 8, 8, 7, 6, 9, 4, 11, 4, 12, 5, 13, 5, 16, 8,
+            16, 10, 17, 11, 18, 13, 19, 16, 21, 18, 22, 21, 11, -4, 10, -6, 8, -7, 7, -8, 4, -8, 3,
+            -6, 4, -5,
+        ],
+    ),
+    (16, &[10, 21, 22, 21, 3, 0, 15, 0, -1, -1, 9, 10, 15, 10]),
+    (
+        14,
+        &[
+            10, 25, 2, -7, -1, -1, 10, 25, 3, -7, -1, -1, 10, 25, 16, 25, -1, -1, 2, -7, 9, -7,
+        ],
+    ),
+    (14, &[5, 21, 13, -3]),
+    (
+        14,
+        &[
+            14, 25, 7, -7, -1, -1, 16, 25, 8, -7, -1, -1, 8, 25, 16, 25, -1, -1, 1, -7, 8, -7,
+        ],
+    ),
+    (
+        16,
+        &[
+            9, 15, 12, 18, 13, 15, -1, -1, 6, 12, 12, 17, 16, 12, -1, -1, 12, 17, 8, 0,
+        ],
+    ),
+    (16, &[0, -2, 16, -2]),
+    (10, &[11, 21, 9, 20, 8, 18, 8, 15, 10, 16, 9, 17]),
+    (
+        12,
+        &[
+            11, 8, 11, 9, 10, 9, 9, 10, 8, 10, 6, 9, 5, 8, 3, 4, 3, 1, 4, 0, 6, 0, 7, 1, 9, 2, 11,
+            6, -1, -1, 12, 10, 10, 2, 9, 0, 10, 0, 12, 1, 13, 2,
+        ],
+    ),
+    (
+        12,
+        &[
+            1, 2, 3, 3, 5, 5, 6, 7, 8, 8, 9, 10, 10, 11, 11, 13, 11, 14, 12, 16, 12, 21, 10, 21, 9,
+            20, 7, 16, 3, 2, 4, 0, 7, 0, 9, 1, 10, 3, 11, 4, 11, 9, 10, 9, 12, 8, 15, 8,
+        ],
+    ),
+    (
+        11,
+        &[
+            11, 8, 11, 10, 8, 10, 6, 9, 5, 8, 4, 6, 3, 5, 3, 1, 4, 0, 7, 0, 9, 1, 11, 1, 12, 2,
+        ],
+    ),
+    (
+        12,
+        &[
+            11, 8, 11, 9, 10, 9, 9, 10, 8, 10, 6, 9, 5, 8, 3, 4, 3, 1, 4, 0, 6, 0, 7, 1, 9, 2, 11,
+            6, -1, -1, 16, 21, 10, 2, 9, 0, 10, 0, 12, 1, 13, 2,
+        ],
+    ),
+    (
+        10,
+        &[
+            1, 2, 9, 6, 10, 8, 10, 9, 9, 10, 7, 10, 6, 8, 5, 7, 4, 5, 3, 4, 3, 1, 4, 0, 8, 0, 10,
+            1, 11, 2,
+        ],
+    ),
+    (
+        9,
+        &[
+            1, 2, 3, 3, 5, 5, 6, 7, 8, 8, 9, 10, 10, 11, 11, 13, 11, 14, 12, 16, 12, 21, 10, 21, 9,
+            20, 7, 16, 2, -4, 2, -6, 1, -8, 0, -8, -1, -7, 0, -5, 2, -4, 3, -2, 5, -1, 6, 0, 8, 1,
+            9, 2, 10, 2,
+        ],
+    ),
+    (
+        12,
+        &[
+            11, 8, 11, 9, 10, 9, 9, 10, 8, 10, 6, 9, 5, 8, 3, 4, 3, 1, 4, 0, 6, 0, 7, 1, 9, 2, 11,
+            6, -1, -1, 12, 10, 8, -4, 6, -6, 5, -8, 2, -8, 2, -6, 4, -4, 8, -2, 9, 0, 11, 0, 12, 2,
+            13, 2,
+        ],
+    ),
+    (
+        12,
+        &[
+            1, 2, 3, 3, 5, 5, 6, 7, 8, 8, 9, 10, 10, 11, 11, 13, 11, 14, 12, 16, 12, 21, 10, 21, 9,
+            20, 7, 16, 2, 0, 4, 2, 5, 5, 6, 6, 7, 8, 9, 10, 10, 10, 11, 9, 11, 8, 9, 2, 9, 1, 10,
+            0, 12, 1, 13, 2,
+        ],
+    ),
+    (
+        8,
+        &[
+            1, 2, 3, 3, 5, 5, 6, 7, 7, 8, 8, 10, 6, 2, 5, 1, 6, 0, 8, 1, 9, 2, -1, -1, 9, 14, 10,
+            15, 11, 14, 9, 13, 9, 14,
+        ],
+    ),
+    (
+        8,
+        &[
+            1, 2, 3, 3, 5, 5, 6, 7, 7, 8, 8, 10, 4, -4, 2, -6, 1, -8, -2, -8, -2, -6, 0, -4, 4, -2,
+            5, 0, 7, 0, 8, 2, 9, 2, -1, -1, 9, 14, 10, 15, 11, 14, 9, 13, 9, 14,
+        ],
+    ),
+    (
+        12,
+        &[
+            1, 2, 3, 3, 5, 5, 6, 7, 8, 8, 9, 10, 10, 11, 11, 13, 11, 14, 12, 16, 12, 21, 10, 21, 9,
+            20, 7, 16, 2, 0, 4, 2, 5, 5, 6, 6, 7, 8, 9, 10, 10, 10, 12, 9, 9, 6, 7, 5, 5, 5, 7, 4,
+            8, 3, 8, 2, 9, 0, 10, 0, 12, 1, 13, 2,
+        ],
+    ),
+    (
+        7,
+        &[
+            1, 2, 3, 3, 5, 5, 6, 7, 8, 8, 9, 10, 10, 11, 11, 13, 11, 14, 12, 16, 12, 21, 10, 21, 9,
+            20, 7, 16, 4, 3, 4, 1, 5, 0, 7, 1, 8, 2,
+        ],
+    ),
+    (
+        15,
+        &[
+            1, 2, 3, 3, 4, 5, 5, 6, 5, 8, 6, 10, 2, 0, 3, 2, 4, 5, 5, 6, 6, 8, 8, 10, 9, 10, 10, 9,
+            10, 8, 7, 0, 8, 2, 9, 5, 10, 6, 11, 8, 13, 10, 15, 9, 14, 8, 13, 2, 12, 1, 14, 0, 16,
+            2,
+        ],
+    ),
+    (
+        11,
+        &[
+            1, 2, 3, 3, 4, 5, 5, 6, 5, 8, 6, 10, 2, 0, 4, 2, 5, 5, 6, 6, 7, 8, 8, 9, 8, 10, 9, 10,
+            11, 9, 10, 8, 9, 2, 8, 1, 10, 0, 12, 2,
+        ],
+    ),
+    (
+        11,
+        &[
+            9, 10, 8, 10, 6, 9, 5, 8, 4, 6, 3, 5, 3, 1, 4, 0, 6, 0, 8, 1, 9, 2, 10, 4, 11, 5, 11,
+            10, 9, 10, 10, 9, 12, 8, 14, 8,
+        ],
+    ),
+    (
+        11,
+        &[
+            1, 2, 3, 3, 4, 5, 5, 6, 7, 10, 0, -8, -1, -1, 4, 4, 6, 8, 8, 9, 9, 10, 10, 10, 12, 9,
+            12, 7, 10, 3, 8, 1, 6, 0, 4, 0, 3, 2,
+        ],
+    ),
+    (
+        12,
+        &[
+            11, 8, 11, 9, 10, 9, 9, 10, 8, 10, 6, 9, 5, 8, 3, 4, 3, 1, 4, 0, 6, 0, 7, 1, 9, 2, 11,
+            6, -1, -1, 12, 10, 7, -6, 6, -8, 5, -8, 5, -7, 8, -4, 9, -2, 11, -1, 12, 1, 13, 2,
+        ],
+    ),
+    (
+        10,
+        &[
+            1, 2, 3, 3, 4, 5, 5, 6, 7, 10, 7, 9, 8, 8, 9, 9, 11, 10, 8, 2, 7, 1, 8, 0, 10, 1, 11, 2,
+        ],
+    ),
+    (
+        10,
+        &[
+            1, 2, 3, 3, 4, 5, 6, 6, 8, 10, 9, 8, 9, 7, 10, 5, 10, 4, 9, 2, 7, 0, 6, 0, 4, 1, 3, 2,
+        ],
+    ),
+    (
+        8,
+        &[
+            1, 2, 5, 6, 6, 8, 6, 9, 10, 17, 6, 2, 5, 1, 6, 0, 8, 1, 9, 2, -1, -1, 5, 11, 11, 11,
+        ],
+    ),
+    (
+        12,
+        &[
+            1, 2, 3, 3, 4, 5, 5, 6, 5, 8, 6, 10, 4, 3, 4, 1, 5, 0, 6, 0, 7, 1, 7, 2, 10, 5, 11, 7,
+            12, 10, 9, 2, 9, 1, 10, 0, 12, 1, 13, 2,
+        ],
+    ),
+    (
+        12,
+        &[
+            1, 2, 3, 3, 4, 5, 5, 6, 6, 8, 6, 0, 7, 1, 8, 3, 9, 4, 12, 10, 13, 9, 15, 8,
+        ],
+    ),
+    (
+        15,
+        &[
+            1, 2, 3, 3, 4, 5, 5, 6, 5, 8, 6, 10, 6, 6, 5, 4, 5, 1, 6, 0, 8, 4, 9, 5, 10, 7, 10, 0,
+            11, 1, 12, 3, 13, 4, 14, 6, 14, 8, 15, 10, 16, 9, 18, 8,
+        ],
+    ),
+    (
+        12,
+        &[
+            1, 2, 4, 5, 5, 7, 8, 10, 9, 9, 9, 1, 10, 0, 12, 1, 13, 2, -1, -1, 13, 10, 5, 0,
+        ],
+    ),
+    (
+        11,
+        &[
+            1, 2, 3, 3, 4, 5, 5, 6, 5, 8, 6, 10, 4, 3, 4, 1, 5, 0, 6, 0, 7, 1, 7, 2, 10, 5, 11, 7,
+            12, 10, 7, -4, 5, -6, 4, -8, 1, -8, 1, -6, 3, -4, 7, -2, 8, 0, 10, 0, 11, 2, 12, 2,
+        ],
+    ),
+    (
+        11,
+        &[
+            1, 2, 3, 4, 4, 6, 5, 7, 6, 9, 6, 10, 12, 10, 3, 0, 9, 0, 11, 1, 12, 2,
+        ],
+    ),
+    (
+        14,
+        &[
+            14, 25, 12, 24, 11, 23, 9, 19, 10, 17, 11, 16, 11, 12, 8, 10, -1, -1, 12, 24, 10, 20,
+            11, 18, 12, 17, 12, 13, 10, 11, 6, 9, 10, 7, 10, 3, 7, 0, 5, -4, 6, -6, -1, -1, 8, 8,
+            9, 6, 9, 4, 6, 1, 4, -3, 5, -5, 7, -7,
+        ],
+    ),
+    (8, &[10, 25, 2, -7]),
+    (
+        14,
+        &[
+            10, 25, 12, 24, 13, 23, 14, 21, 12, 17, 9, 14, 9, 12, 10, 10, -1, -1, 12, 24, 13, 22,
+            11, 18, 8, 15, 8, 11, 12, 9, 8, 7, 6, 5, 6, 1, 7, 0, 8, -2, 6, -6, -1, -1, 10, 8, 7, 6,
+            7, 2, 8, 1, 9, -1, 7, -5, 6, -6, 3, -7,
+        ],
+    ),
+    (
+        24,
+        &[
+            4, 6, 5, 8, 6, 11, 9, 12, 11, 12, 12, 11, 16, 8, 18, 7, 20, 7, 22, 8, 23, 10, -1, -1,
+            5, 8, 6, 10, 8, 11, 10, 11, 12, 10, 16, 7, 17, 6, 19, 6, 22, 7, 23, 10, 24, 12,
+        ],
+    ),
+];
+
+/// The strokes making up one character
+pub struct Glyph {
+    pub advance: f32,
+    pub strokes: Vec<Polyline>,
+}
+
+/// A single-stroke font, where every character is drawn with a few pen strokes
+pub struct Font {
+    glyphs: HashMap<char, Glyph>,
+    cap_height: f32,
+}
+
+impl Font {
+    /// The built-in Hershey Roman Simplex font, a plain sans serif
+    pub fn simplex() -> Font {
+        Font::from_table(&SIMPLEX)
+    }
+
+    /// The built-in Hershey Roman Complex font, a serif drawn with doubled strokes
+    pub fn complex() -> Font {
+        Font::from_table(&COMPLEX)
+    }
+
+    /// The built-in Hershey Script Simplex font, a slanted joined-up hand
+    pub fn script() -> Font {
+        Font::from_table(&SCRIPT)
+    }
+
+    fn from_table(table: &[(i8, &[i8]); 95]) -> Font {
+        let glyphs = table
+            .iter()
+            .enumerate()
+            .map(|(i, (advance, vertices))| {
+                let mut strokes = vec![];
+                let mut stroke: Polyline = vec![];
+
+                for pair in vertices.chunks(2) {
+                    if pair == [-1, -1] {
+                        strokes.push(std::mem::take(&mut stroke));
+                    } else {
+                        stroke.push((pair[0] as f32, pair[1] as f32));
+                    }
+                }
+                strokes.push(stroke);
+
+                let glyph = Glyph {
+                    advance: *advance as f32,
+                    strokes: strokes.into_iter().filter(|s| s.len() > 1).collect(),
+                };
+
+                ((b' ' + i as u8) as char, glyph)
+            })
+            .collect();
+
+        Font {
+            glyphs,
+            cap_height: CAP_HEIGHT,
+        }
+    }
+
+    /// Looks up a built-in font by name
+    pub fn named(name: &str) -> Option<Font> {
+        match name {
+            "futural" | "simplex" => Some(Font::simplex()),
+            "timesr" | "complex" => Some(Font::complex()),
+            "scripts" | "script" => Some(Font::script()),
+            _ => None,
+        }
+    }
+
+    /// Looks up a character, falling back to '?' for ones the font doesn't have
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    /// Lays out one line of text with its baseline starting at the origin
+    ///
+    /// Capital letters are `height` tall. Returns the strokes and the width of the line.
+    pub fn line(&self, text: &str, height: f32) -> (Vec<Polyline>, f32) {
+        let scale = height / self.cap_height;
+        let mut strokes = vec![];
+        let mut x = 0.0;
+
+        for c in text.chars() {
+            let glyph = match self.glyph(c) {
+                Some(g) => g,
+                None => continue,
+            };
+
+            for stroke in &glyph.strokes {
+                strokes.push(
+                    stroke
+                        .iter()
+                        .map(|&(gx, gy)| -> Point { (x + gx * scale, gy * scale) })
+                        .collect(),
+                );
+            }
+
+            x += glyph.advance * scale;
+        }
+
+        (strokes, x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_font_has_every_printable_character() {
+        for name in FONT_NAMES {
+            let font = Font::named(name).unwrap();
+            for c in ' '..='~' {
+                let glyph = &font.glyphs[&c];
+                assert!(glyph.advance > 0.0, "{} {:?}", name, c);
+                assert_eq!(glyph.strokes.is_empty(), c == ' ', "{} {:?}", name, c);
+            }
+        }
+    }
+
+    #[test]
+    fn missing_characters_fall_back_to_a_question_mark() {
+        let font = Font::simplex();
+        assert_eq!(
+            font.glyph('é').unwrap().advance,
+            font.glyph('?').unwrap().advance
+        );
+    }
+
+    #[test]
+    fn line_scales_to_height() {
+        let font = Font::complex();
+        let (_, small) = font.line("Blot", 5.0);
+        let (_, big) = font.line("Blot", 10.0);
+        assert!(small > 0.0);
+        assert!((big - 2.0 * small).abs() < 1e-4);
+    }
+}
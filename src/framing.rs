@@ -0,0 +1,318 @@
+use cobs2::cobs;
+
+use crate::comms::{BlotComms, BlotPacket};
+use crate::error::BlotError;
+
+/// How packets are delimited on the wire
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Framing {
+    /// COBS encoded and terminated by 0x00, as the CLI sends to the Blot
+    Cobs,
+    /// Raw and terminated by 0x0a (LF), as the Blot replies to the CLI
+    Line,
+}
+
+/// Turns a stream of bytes into packets, however the bytes are split up as they arrive
+///
+/// Frames that can't be decoded are skipped up to the next delimiter and counted, so one
+/// corrupted frame doesn't take the rest of the stream down with it.
+pub struct FrameDecoder {
+    framing: Framing,
+    buffer: Vec<u8>,
+    dropped: u64,
+}
+
+impl FrameDecoder {
+    pub fn new(framing: Framing) -> FrameDecoder {
+        FrameDecoder {
+            framing,
+            buffer: vec![],
+            dropped: 0,
+        }
+    }
+
+    /// Adds bytes read from the wire
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Number of malformed frames skipped so far
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Number of bytes waiting for the rest of their frame
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Decodes the next frame, or returns `None` until a whole one has arrived
+    ///
+    /// A malformed frame is dropped and returned as an error, and the following call carries
+    /// on from the frame after it.
+    pub fn next_frame(&mut self) -> Option<Result<BlotPacket, BlotError>> {
+        let result = match self.framing {
+            Framing::Cobs => self.next_cobs_frame()?,
+            Framing::Line => self.next_line_frame()?,
+        };
+
+        if result.is_err() {
+            self.dropped += 1;
+        }
+
+        Some(result)
+    }
+
+    /// Decodes the next well-formed packet, skipping any malformed frames before it
+    pub fn next_packet(&mut self) -> Option<BlotPacket> {
+        loop {
+            if let Ok(packet) = self.next_frame()? {
+                return Some(packet);
+            }
+        }
+    }
+
+    /// Gives up on a partial frame once the line has gone quiet, if it contains a delimiter
+    ///
+    /// Raw frames can contain 0x0a, so a frame with a corrupted length field can look like it is
+    /// still arriving. When nothing more turns up, resyncing on the LF lets the frames after it
+    /// through. COBS frames never contain 0x00, so they are never ambiguous like this.
+    pub fn resync(&mut self) -> Option<BlotError> {
+        if self.framing != Framing::Line {
+            return None;
+        }
+
+        let end = self.buffer.iter().position(|&b| b == 0x0a)?;
+        self.buffer.drain(..=end);
+        self.dropped += 1;
+
+        Some(BlotError::Framing(format!(
+            "gave up on a {} byte frame that never finished",
+            end + 1
+        )))
+    }
+
+    fn next_cobs_frame(&mut self) -> Option<Result<BlotPacket, BlotError>> {
+        loop {
+            let end = self.buffer.iter().position(|&b| b == 0)?;
+            let frame: Vec<u8> = self.buffer.drain(..=end).collect();
+
+            // Back to back delimiters are harmless padding rather than a broken frame
+            if end == 0 {
+                continue;
+            }
+
+            return Some(
+                cobs::decode_vector(&frame[..end])
+                    .map_err(|e| BlotError::Framing(format!("invalid COBS encoding: {:?}", e)))
+                    .and_then(|decoded| Self::unpack_exact(&decoded)),
+            );
+        }
+    }
+
+    // Raw packets can contain 0x0a themselves, so the length fields say where the frame
+    // should end and the LF there confirms it
+    fn next_line_frame(&mut self) -> Option<Result<BlotPacket, BlotError>> {
+        let msg_length = *self.buffer.first()? as usize;
+        let payload_length = *self.buffer.get(msg_length + 1)? as usize;
+        let length = msg_length + 2 + payload_length + 1;
+
+        let terminator = *self.buffer.get(length)?;
+        if terminator == 0x0a {
+            let frame: Vec<u8> = self.buffer.drain(..=length).collect();
+            return Some(BlotComms::unpack(&frame[..length]));
+        }
+
+        // The lengths don't line up with a delimiter, so resync on the next LF
+        let end = self
+            .buffer
+            .iter()
+            .position(|&b| b == 0x0a)
+            .unwrap_or(self.buffer.len() - 1);
+        let skipped = self.buffer.drain(..=end).count();
+
+        Some(Err(BlotError::Framing(format!(
+            "skipped {} bytes that didn't form a packet",
+            skipped
+        ))))
+    }
+
+    fn unpack_exact(decoded: &[u8]) -> Result<BlotPacket, BlotError> {
+        let packet = BlotComms::unpack(decoded)?;
+        let length = packet.msg.len() + 2 + packet.payload.len() + 1;
+
+        if decoded.len() != length {
+            return Err(BlotError::Framing(format!(
+                "packet is {} bytes but its lengths add up to {}",
+                decoded.len(),
+                length
+            )));
+        }
+
+        Ok(packet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comms::PacketState;
+    use uuid::Uuid;
+
+    fn packet(msg: &str, payload: &[u8], index: u8) -> BlotPacket {
+        BlotPacket {
+            id: Uuid::new_v4(),
+            msg: msg.to_string(),
+            payload: payload.to_vec(),
+            index: Some(index),
+            state: PacketState::Queued,
+        }
+    }
+
+    fn cobs_frame(packet: &BlotPacket) -> Vec<u8> {
+        let mut frame = cobs::encode_vector(&BlotComms::pack(packet).unwrap()).unwrap();
+        frame.push(0);
+        frame
+    }
+
+    fn line_frame(packet: &BlotPacket) -> Vec<u8> {
+        let mut frame = BlotComms::pack(packet).unwrap();
+        frame.push(0x0a);
+        frame
+    }
+
+    // Decodes everything that has arrived, malformed frames included
+    fn decode_all(decoder: &mut FrameDecoder) -> Vec<Result<BlotPacket, BlotError>> {
+        std::iter::from_fn(|| decoder.next_frame()).collect()
+    }
+
+    #[test]
+    fn cobs_frames_split_across_pushes() {
+        let go = packet("go", &[0, 0, 32, 65, 0, 0, 72, 66], 1);
+        let servo = packet("servo", &1700_u32.to_le_bytes(), 2);
+        let bytes = [cobs_frame(&go), cobs_frame(&servo)].concat();
+
+        let mut decoder = FrameDecoder::new(Framing::Cobs);
+        let mut packets = vec![];
+        for chunk in bytes.chunks(3) {
+            decoder.push(chunk);
+            packets.extend(std::iter::from_fn(|| decoder.next_packet()));
+        }
+
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].msg, "go");
+        assert_eq!(packets[0].payload, go.payload);
+        assert_eq!(packets[0].index, Some(1));
+        assert_eq!(packets[1].msg, "servo");
+        assert_eq!(packets[1].index, Some(2));
+        assert_eq!(decoder.dropped(), 0);
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn line_frames_split_across_pushes() {
+        // A payload can contain 0x0a, which the length fields tell apart from the delimiter
+        let go = packet("go", &[0x0a, 0, 0, 0, 0x0a, 0, 0, 0], 3);
+        let ack = packet("ack", &[], 3);
+        let bytes = [line_frame(&go), line_frame(&ack)].concat();
+
+        let mut decoder = FrameDecoder::new(Framing::Line);
+        let mut packets = vec![];
+        for byte in bytes {
+            decoder.push(&[byte]);
+            packets.extend(std::iter::from_fn(|| decoder.next_packet()));
+        }
+
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].payload, go.payload);
+        assert_eq!(packets[1].msg, "ack");
+        assert_eq!(decoder.dropped(), 0);
+    }
+
+    #[test]
+    fn corrupted_cobs_frame_is_skipped() {
+        let ack = packet("ack", &[], 4);
+        // The code byte says four bytes follow, but the frame ends after two
+        let corrupted = vec![0x05, 0x01, 0x02, 0x00];
+        // Valid COBS, but the packet inside is longer than its lengths add up to
+        let mut padded = BlotComms::pack(&ack).unwrap();
+        padded.push(0x07);
+        let mut padded = cobs::encode_vector(&padded).unwrap();
+        padded.push(0);
+
+        let mut decoder = FrameDecoder::new(Framing::Cobs);
+        decoder.push(&[corrupted, padded, cobs_frame(&ack)].concat());
+        let frames = decode_all(&mut decoder);
+
+        assert_eq!(frames.len(), 3);
+        assert!(matches!(frames[0], Err(BlotError::Framing(_))));
+        assert!(matches!(frames[1], Err(BlotError::Framing(_))));
+        assert_eq!(frames[2].as_ref().unwrap().index, Some(4));
+        assert_eq!(decoder.dropped(), 2);
+    }
+
+    #[test]
+    fn back_to_back_delimiters_are_not_frames() {
+        let ack = packet("ack", &[], 5);
+        let frame = cobs_frame(&ack);
+
+        let mut decoder = FrameDecoder::new(Framing::Cobs);
+        decoder.push(&[&[0, 0][..], &frame, &[0][..], &frame].concat());
+        let frames = decode_all(&mut decoder);
+
+        assert_eq!(frames.len(), 2);
+        assert!(frames.iter().all(|f| f.is_ok()));
+        assert_eq!(decoder.dropped(), 0);
+    }
+
+    #[test]
+    fn line_frame_with_wrong_length_resyncs_on_next_lf() {
+        // A go packet claiming a 9 byte payload when it has 8, one of them 0x0a
+        let mut bad = vec![2, b'g', b'o', 9];
+        bad.extend_from_slice(&[0, 0, 0, 0x0a, 0, 0, 0, 0]);
+        bad.extend_from_slice(&[5, 0x0a]);
+        let ack = packet("ack", &[], 6);
+
+        let mut decoder = FrameDecoder::new(Framing::Line);
+        decoder.push(&[bad, line_frame(&ack)].concat());
+        let frames = decode_all(&mut decoder);
+
+        // The embedded LF splits the bad frame in two, and both halves are dropped
+        assert_eq!(frames.len(), 3);
+        assert!(frames[..2].iter().all(|f| f.is_err()));
+        let decoded = frames[2].as_ref().unwrap();
+        assert_eq!(decoded.msg, "ack");
+        assert_eq!(decoded.index, Some(6));
+        assert_eq!(decoder.dropped(), 2);
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn resync_gives_up_on_unfinished_line_frame() {
+        // The payload length promises far more bytes than ever arrive
+        let unfinished = vec![3, b'a', b'c', b'k', 50, 0x0a];
+        let ack = packet("ack", &[], 7);
+
+        let mut decoder = FrameDecoder::new(Framing::Line);
+        decoder.push(&unfinished);
+        assert!(decoder.next_frame().is_none());
+
+        assert!(matches!(decoder.resync(), Some(BlotError::Framing(_))));
+        assert_eq!(decoder.dropped(), 1);
+        assert_eq!(decoder.pending(), 0);
+        assert!(decoder.resync().is_none());
+
+        decoder.push(&line_frame(&ack));
+        assert_eq!(decoder.next_packet().unwrap().index, Some(7));
+    }
+
+    #[test]
+    fn resync_leaves_cobs_frames_alone() {
+        let mut decoder = FrameDecoder::new(Framing::Cobs);
+        decoder.push(&[0x03, b'a', 0x0a]);
+
+        assert!(decoder.resync().is_none());
+        assert_eq!(decoder.pending(), 3);
+        assert_eq!(decoder.dropped(), 0);
+    }
+}
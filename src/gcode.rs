@@ -0,0 +1,306 @@
+use std::f32::consts::TAU;
+
+use crate::plot::{self, Point, Polyline, Tracer};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Motion {
+    Linear,
+    Clockwise,
+    Counterclockwise,
+}
+
+struct Machine {
+    pen: Tracer,
+    z: f32,
+    motion: Motion,
+    absolute: bool,
+    absolute_arcs: bool,
+    units: f32,
+}
+
+impl Machine {
+    fn arc_to(&mut self, target: Point, center: Point, tolerance: f32) {
+        let start = self.pen.position;
+        let radius = plot::distance(center, start);
+        let start_angle = (start.1 - center.1).atan2(start.0 - center.0);
+        let end_angle = (target.1 - center.1).atan2(target.0 - center.0);
+
+        // An arc ending where it starts is a full circle
+        let mut sweep = end_angle - start_angle;
+        if self.motion == Motion::Counterclockwise && sweep <= 0.0 {
+            sweep += TAU;
+        } else if self.motion == Motion::Clockwise && sweep >= 0.0 {
+            sweep -= TAU;
+        }
+
+        let mut points = vec![];
+        plot::flatten_arc(&mut points, center, radius, start_angle, sweep, tolerance);
+        // Land exactly on the requested end point rather than on a rounded one
+        points.pop();
+        points.push(target);
+
+        for point in points {
+            self.pen.line_to(point);
+        }
+    }
+}
+
+/// Parses G-code into polylines in millimetres
+///
+/// G0/G1 moves, G2/G3 arcs, G20/G21 units and G90/G91 distance modes are supported.
+/// M3/M4 put the pen down and M5 lifts it, as does moving Z above 0.
+pub fn parse(source: &str, tolerance: f32) -> Result<Vec<Polyline>, String> {
+    let mut machine = Machine {
+        pen: Tracer::new(),
+        z: 0.0,
+        motion: Motion::Linear,
+        absolute: true,
+        absolute_arcs: false,
+        units: 1.0,
+    };
+
+    for (line_number, line) in source.lines().enumerate() {
+        let words = words(line).map_err(|e| format!("Line {}: {}", line_number + 1, e))?;
+        let word = |letter: char| words.iter().find(|(l, _)| *l == letter).map(|(_, v)| *v);
+
+        let mut pen_up_after = false;
+        let mut end_program = false;
+
+        // Codes are compared in tenths so that G90.1 and G91.1 can be told apart from G90 and G91
+        for &(letter, value) in &words {
+            match (letter, (value * 10.0).round() as i32) {
+                ('G', 0) | ('G', 10) => machine.motion = Motion::Linear,
+                ('G', 20) => machine.motion = Motion::Clockwise,
+                ('G', 30) => machine.motion = Motion::Counterclockwise,
+                ('G', 200) => machine.units = 25.4,
+                ('G', 210) => machine.units = 1.0,
+                ('G', 900) => machine.absolute = true,
+                ('G', 910) => machine.absolute = false,
+                ('G', 901) => machine.absolute_arcs = true,
+                ('G', 911) => machine.absolute_arcs = false,
+                ('M', 30) | ('M', 40) => machine.pen.set_pen(true),
+                ('M', 50) => pen_up_after = true,
+                ('M', 20) | ('M', 300) => end_program = true,
+                _ => {}
+            }
+        }
+
+        let units = machine.units;
+        let axis = |value: Option<f32>, current: f32, absolute: bool| match value {
+            Some(v) if absolute => v * units,
+            Some(v) => current + v * units,
+            None => current,
+        };
+
+        // Lower the pen before moving, but only lift it once the move is done
+        if let Some(z) = word('Z') {
+            machine.z = axis(Some(z), machine.z, machine.absolute);
+            if machine.z <= 0.0 {
+                machine.pen.set_pen(true);
+            } else {
+                pen_up_after = true;
+            }
+        }
+
+        let target = (
+            axis(word('X'), machine.pen.position.0, machine.absolute),
+            axis(word('Y'), machine.pen.position.1, machine.absolute),
+        );
+        let moved = word('X').is_some() || word('Y').is_some();
+
+        match machine.motion {
+            Motion::Linear if moved => machine.pen.line_to(target),
+            Motion::Clockwise | Motion::Counterclockwise
+                if moved || word('I').is_some() || word('J').is_some() =>
+            {
+                let center = match word('R') {
+                    Some(r) => {
+                        let center =
+                            radius_center(machine.pen.position, target, r * units, machine.motion);
+
+                        match center {
+                            Some(c) => c,
+                            None => {
+                                return Err(format!(
+                                    "Line {}: arc radius is too small to reach the end point",
+                                    line_number + 1
+                                ))
+                            }
+                        }
+                    }
+                    None => (
+                        axis(word('I'), machine.pen.position.0, machine.absolute_arcs),
+                        axis(word('J'), machine.pen.position.1, machine.absolute_arcs),
+                    ),
+                };
+
+                machine.arc_to(target, center, tolerance);
+            }
+            _ => {}
+        }
+
+        if pen_up_after {
+            machine.pen.set_pen(false);
+        }
+
+        if end_program {
+            break;
+        }
+    }
+
+    Ok(machine.pen.finish())
+}
+
+// Finds the center of an arc given by its radius, where a negative radius picks the longer arc
+fn radius_center(start: Point, end: Point, radius: f32, motion: Motion) -> Option<Point> {
+    let chord = plot::distance(start, end);
+    let half = chord / 2.0;
+
+    if chord == 0.0 || radius.abs() < half - 0.0001 {
+        return None;
+    }
+
+    let offset = (radius * radius - half * half).max(0.0).sqrt();
+    let direction = ((end.0 - start.0) / chord, (end.1 - start.1) / chord);
+    let left = (-direction.1, direction.0);
+
+    let mut side = if motion == Motion::Counterclockwise {
+        1.0
+    } else {
+        -1.0
+    };
+    if radius < 0.0 {
+        side = -side;
+    }
+
+    Some((
+        (start.0 + end.0) / 2.0 + left.0 * offset * side,
+        (start.1 + end.1) / 2.0 + left.1 * offset * side,
+    ))
+}
+
+// Splits a line into letter and number pairs, dropping comments
+fn words(line: &str) -> Result<Vec<(char, f32)>, String> {
+    let mut words = vec![];
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => break,
+            '(' => {
+                for c in chars.by_ref() {
+                    if c == ')' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_ascii_alphabetic() => {
+                let mut number = String::new();
+                while let Some(&n) = chars.peek() {
+                    if n.is_ascii_digit() || n == '.' || n == '-' || n == '+' {
+                        number.push(n);
+                        chars.next();
+                    } else if n.is_whitespace() && number.is_empty() {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                let value = number
+                    .parse::<f32>()
+                    .map_err(|_| format!("invalid number after {}", c))?;
+                words.push((c.to_ascii_uppercase(), value));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Point, b: Point) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    #[test]
+    fn pen_follows_m3_m5_and_z() {
+        let gcode = "G0 X1 Y1\nM3\nG1 X5 Y1\nM5\nG0 X10 Y10\nG1 Z-1\nG1 X20\nG1 Z2 X30\n";
+
+        let polylines = parse(gcode, 0.1).unwrap();
+
+        // The pen lifts only after the move that raised Z
+        assert_eq!(
+            polylines,
+            vec![
+                vec![(1.0, 1.0), (5.0, 1.0)],
+                vec![(10.0, 10.0), (20.0, 10.0), (30.0, 10.0)]
+            ]
+        );
+    }
+
+    #[test]
+    fn inches_and_relative_moves() {
+        let gcode = "G20 G91\nM3\nG1 X1 Y0\nG1 X0 Y1\nG21\nG1 X-10\nM5\n";
+
+        let polylines = parse(gcode, 0.1).unwrap();
+
+        assert_eq!(polylines.len(), 1);
+        let expected = [(0.0, 0.0), (25.4, 0.0), (25.4, 25.4), (15.4, 25.4)];
+        for (&point, &expected) in polylines[0].iter().zip(&expected) {
+            assert!(close(point, expected));
+        }
+    }
+
+    #[test]
+    fn arc_with_center_offset_ends_on_target() {
+        let gcode = "G0 X10 Y0\nM3\nG3 X-10 Y0 I-10 J0\nM5\n";
+
+        let polylines = parse(gcode, 0.01).unwrap();
+        let arc = &polylines[0];
+
+        // Counterclockwise from (10, 0) to (-10, 0) goes over the top
+        assert_eq!(arc.last(), Some(&(-10.0, 0.0)));
+        assert!(arc.len() > 3);
+        for &(x, y) in arc {
+            assert!((plot::distance((0.0, 0.0), (x, y)) - 10.0).abs() < 1e-3);
+            assert!(y >= -1e-3);
+        }
+    }
+
+    #[test]
+    fn negative_radius_picks_the_longer_arc() {
+        let short = parse("M3\nG2 X10 Y0 R5\nM5\n", 0.01).unwrap();
+        let long = parse("M3\nG2 X10 Y10 R-10\nM5\n", 0.01).unwrap();
+        let length = |polyline: &Polyline| {
+            polyline
+                .windows(2)
+                .map(|w| plot::distance(w[0], w[1]))
+                .sum::<f32>()
+        };
+
+        // A half circle of radius 5, and three quarters of a circle of radius 10
+        assert!((length(&short[0]) - 5.0 * std::f32::consts::PI).abs() < 0.05);
+        assert!((length(&long[0]) - 15.0 * std::f32::consts::PI).abs() < 0.1);
+    }
+
+    #[test]
+    fn radius_too_small_for_the_arc_is_an_error() {
+        let err = parse("G0 X0 Y0\nM3\nG2 X10 Y0 R2\n", 0.1).unwrap_err();
+
+        assert!(err.starts_with("Line 3:"), "{}", err);
+    }
+
+    #[test]
+    fn comments_are_ignored_and_bad_numbers_reported() {
+        let polylines = parse("M3 (pen down X99)\nG1 X5 ; Y99\nM5", 0.1).unwrap();
+        assert_eq!(polylines, vec![vec![(0.0, 0.0), (5.0, 0.0)]]);
+
+        let err = parse("G1 X5\nG1 Xabc", 0.1).unwrap_err();
+        assert_eq!(err, "Line 2: invalid number after X");
+    }
+}
@@ -0,0 +1,286 @@
+use std::f32::consts::TAU;
+
+use crate::font::Font;
+use crate::plot::{self, Point, Polyline, Tracer};
+
+// Label character size after IN, in millimetres
+const DEFAULT_LABEL_WIDTH: f32 = 1.9;
+const DEFAULT_LABEL_HEIGHT: f32 = 2.7;
+
+// Labels are terminated by ETX unless changed with DT, which isn't supported
+const LABEL_TERMINATOR: char = '\x03';
+
+struct Plotter {
+    pen: Tracer,
+    absolute: bool,
+    units_per_mm: f32,
+    label_size: (f32, f32),
+}
+
+impl Plotter {
+    fn target(&self, x: f32, y: f32) -> Point {
+        let (x, y) = (x / self.units_per_mm, y / self.units_per_mm);
+
+        if self.absolute {
+            (x, y)
+        } else {
+            (self.pen.position.0 + x, self.pen.position.1 + y)
+        }
+    }
+
+    fn move_through(&mut self, params: &[f32]) {
+        for pair in params.chunks_exact(2) {
+            let target = self.target(pair[0], pair[1]);
+            self.pen.line_to(target);
+        }
+    }
+
+    fn draw(&mut self, stroke: &[Point]) {
+        if stroke.is_empty() {
+            return;
+        }
+
+        self.pen.set_pen(false);
+        self.pen.line_to(stroke[0]);
+        self.pen.set_pen(true);
+        for &point in &stroke[1..] {
+            self.pen.line_to(point);
+        }
+    }
+
+    fn circle(&mut self, radius: f32, chord_angle: Option<f32>, tolerance: f32) {
+        let center = self.pen.position;
+        let was_down = self.pen.pen_down;
+        let radius = radius / self.units_per_mm;
+
+        // The chord angle in degrees sets how finely the circle is drawn
+        let tolerance = match chord_angle {
+            Some(angle) => radius.abs() * (1.0 - (angle.to_radians() / 2.0).cos()),
+            None => tolerance,
+        };
+
+        let mut circle = vec![(center.0 + radius, center.1)];
+        plot::flatten_arc(&mut circle, center, radius, 0.0, TAU, tolerance);
+        self.draw(&circle);
+
+        self.pen.set_pen(false);
+        self.pen.line_to(center);
+        self.pen.set_pen(was_down);
+    }
+
+    fn label(&mut self, text: &str, font: &Font) {
+        let was_down = self.pen.pen_down;
+        let (width, height) = self.label_size;
+        // Stretch characters horizontally when SI changes their proportions
+        let stretch = (width / height) / (DEFAULT_LABEL_WIDTH / DEFAULT_LABEL_HEIGHT);
+
+        let start_x = self.pen.position.0;
+        let mut cursor = self.pen.position;
+
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                cursor.1 -= height * 2.0;
+            }
+
+            for (j, run) in line.split('\r').enumerate() {
+                if j > 0 {
+                    cursor.0 = start_x;
+                }
+
+                let (strokes, advance) = font.line(run, height);
+                for stroke in strokes {
+                    let stroke: Polyline = stroke
+                        .iter()
+                        .map(|&(x, y)| (cursor.0 + x * stretch, cursor.1 + y))
+                        .collect();
+                    self.draw(&stroke);
+                }
+
+                cursor.0 += advance * stretch;
+            }
+        }
+
+        // The pen ends up after the last character, in the state it started in
+        self.pen.set_pen(false);
+        self.pen.line_to(cursor);
+        self.pen.set_pen(was_down);
+    }
+}
+
+/// Parses HPGL into polylines in millimetres
+///
+/// IN, SP, PU, PD, PA, PR, CI, SI and LB are supported, and other instructions are ignored.
+pub fn parse(source: &str, units_per_mm: f32, tolerance: f32) -> Result<Vec<Polyline>, String> {
+    if !(units_per_mm.is_finite() && units_per_mm > 0.0) {
+        return Err(format!(
+            "HPGL units per mm must be more than 0, not {}",
+            units_per_mm
+        ));
+    }
+
+    let font = Font::simplex();
+    let mut plotter = Plotter {
+        pen: Tracer::new(),
+        absolute: true,
+        units_per_mm,
+        label_size: (DEFAULT_LABEL_WIDTH, DEFAULT_LABEL_HEIGHT),
+    };
+
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+
+    while i + 1 < chars.len() {
+        if !chars[i].is_ascii_alphabetic() {
+            i += 1;
+            continue;
+        }
+
+        let mnemonic: String = chars[i..i + 2].iter().collect::<String>().to_uppercase();
+        i += 2;
+
+        if mnemonic == "LB" {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == LABEL_TERMINATOR)
+                .map(|p| i + p)
+                .unwrap_or(chars.len());
+            let text: String = chars[i..end].iter().collect();
+
+            plotter.label(&text, &font);
+            i = end + 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && chars[i] != ';' && !chars[i].is_ascii_alphabetic() {
+            i += 1;
+        }
+
+        let params = chars[start..i]
+            .iter()
+            .collect::<String>()
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                p.parse::<f32>()
+                    .map_err(|_| format!("Invalid parameter for {}: {}", mnemonic, p))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match mnemonic.as_str() {
+            "IN" => {
+                plotter.pen.set_pen(false);
+                plotter.absolute = true;
+                plotter.label_size = (DEFAULT_LABEL_WIDTH, DEFAULT_LABEL_HEIGHT);
+            }
+            // The Blot only has one pen, so selecting pen 0 just puts it away
+            "SP" if params.first() == Some(&0.0) => plotter.pen.set_pen(false),
+            "PU" => {
+                plotter.pen.set_pen(false);
+                plotter.move_through(&params);
+            }
+            "PD" => {
+                plotter.pen.set_pen(true);
+                plotter.move_through(&params);
+            }
+            "PA" => {
+                plotter.absolute = true;
+                plotter.move_through(&params);
+            }
+            "PR" => {
+                plotter.absolute = false;
+                plotter.move_through(&params);
+            }
+            "CI" => match params.first() {
+                Some(&radius) => plotter.circle(radius, params.get(1).copied(), tolerance),
+                None => return Err("CI needs a radius".to_string()),
+            },
+            "SI" => match params[..] {
+                [width, height] => plotter.label_size = (width * 10.0, height * 10.0),
+                _ => plotter.label_size = (DEFAULT_LABEL_WIDTH, DEFAULT_LABEL_HEIGHT),
+            },
+            _ => {}
+        }
+    }
+
+    Ok(plotter.pen.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Point, b: Point) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    #[test]
+    fn absolute_and_relative_moves_in_plotter_units() {
+        let polylines = parse("IN;PU400,400;PD800,400,800,800;PR;PD-400,0;PU;", 40.0, 0.1).unwrap();
+
+        assert_eq!(
+            polylines,
+            vec![vec![(10.0, 10.0), (20.0, 10.0), (20.0, 20.0), (10.0, 20.0)]]
+        );
+    }
+
+    #[test]
+    fn pen_zero_puts_the_pen_away() {
+        let polylines = parse("PD;PA40,0;SP0;PA80,0;", 40.0, 0.1).unwrap();
+
+        assert_eq!(polylines, vec![vec![(0.0, 0.0), (1.0, 0.0)]]);
+    }
+
+    #[test]
+    fn circle_returns_to_its_center_with_the_pen_as_it_was() {
+        let polylines = parse("PU400,400;CI200;", 40.0, 0.01).unwrap();
+
+        assert_eq!(polylines.len(), 1);
+        for &point in &polylines[0] {
+            assert!((plot::distance((10.0, 10.0), point) - 5.0).abs() < 1e-3);
+        }
+        assert!(close(polylines[0][0], *polylines[0].last().unwrap()));
+    }
+
+    #[test]
+    fn negative_radius_draws_the_same_circle_as_finely() {
+        let positive = parse("PU400,400;CI200;", 40.0, 0.01).unwrap();
+        let negative = parse("PU400,400;CI-200;", 40.0, 0.01).unwrap();
+
+        // It starts on the opposite side, but is subdivided by the size of the radius
+        assert_eq!(positive[0].len(), negative[0].len());
+        assert!(close(negative[0][0], (5.0, 10.0)));
+        for &point in &negative[0] {
+            assert!((plot::distance((10.0, 10.0), point) - 5.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn label_is_drawn_and_the_pen_moves_past_it() {
+        let polylines = parse("PU0,0;LBI\x03PD;PR40,0;", 40.0, 0.1).unwrap();
+
+        // The I is one vertical stroke, then the pen draws on from the end of the label
+        assert_eq!(polylines.len(), 2);
+        let stroke = &polylines[0];
+        assert_eq!(stroke.len(), 2);
+        assert_eq!(stroke[0].0, stroke[1].0);
+        assert!((stroke[0].1 - stroke[1].1).abs() > 1.0);
+        assert!(polylines[1][0].0 > stroke[0].0);
+    }
+
+    #[test]
+    fn units_must_be_positive() {
+        for units in [0.0, -40.0, f32::NAN, f32::INFINITY] {
+            assert!(parse("PD400,400;", units, 0.1).is_err());
+        }
+    }
+
+    #[test]
+    fn bad_parameters_and_missing_radius_are_errors() {
+        assert_eq!(
+            parse("PD1.2.3,2;", 40.0, 0.1).unwrap_err(),
+            "Invalid parameter for PD: 1.2.3"
+        );
+        assert_eq!(parse("CI;", 40.0, 0.1).unwrap_err(), "CI needs a radius");
+    }
+}
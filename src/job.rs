@@ -0,0 +1,288 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::plot::{Move, Point};
+
+/// How far a plot has got, saved after every acknowledged move so it can be resumed
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The drawing or program being plotted, if it came from a file
+    pub source: Option<PathBuf>,
+    /// FNV-1a hash of the source when the job started, to notice if it changed since
+    pub source_hash: Option<String>,
+    /// Where the pen started from, which the moves were planned from
+    pub origin: Point,
+    pub total_moves: usize,
+    /// Moves the Blot has acknowledged, so the next one to send is at this index
+    pub acked_moves: usize,
+    /// Whether the pen was down after the last acknowledged move
+    pub pen_down: bool,
+    /// Where the last acknowledged move left the pen
+    pub position: Point,
+}
+
+/// A plot in progress, with the moves it was planned as
+///
+/// Moves are saved rather than planned again on resume, as the optimizer can stop at a
+/// different order on a second run.
+pub struct Job {
+    pub checkpoint: Checkpoint,
+    pub moves: Vec<Move>,
+    dir: PathBuf,
+}
+
+impl Job {
+    /// Starts a job in `dir`, replacing whatever job was there
+    ///
+    /// Moves that didn't come from a file, such as text or shapes, have no `source` to check
+    /// on resume.
+    pub fn start(
+        dir: &Path,
+        source: Option<&Path>,
+        origin: Point,
+        moves: Vec<Move>,
+    ) -> Result<Job, String> {
+        let source = source
+            .map(|source| {
+                source
+                    .canonicalize()
+                    .map_err(|e| format!("Unable to find {}: {}", source.display(), e))
+            })
+            .transpose()?;
+
+        let job = Job {
+            checkpoint: Checkpoint {
+                source_hash: source.as_deref().map(hash_file).transpose()?,
+                source,
+                origin,
+                total_moves: moves.len(),
+                acked_moves: 0,
+                pen_down: false,
+                position: origin,
+            },
+            moves,
+            dir: dir.to_path_buf(),
+        };
+
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Unable to create {}: {}", dir.display(), e))?;
+        write_atomically(&job.moves_path(), &encode_moves(&job.moves))?;
+        job.save()?;
+
+        Ok(job)
+    }
+
+    /// Loads the job left in `dir`, if there is one
+    pub fn load(dir: &Path) -> Result<Option<Job>, String> {
+        let checkpoint_path = dir.join("job.toml");
+        if !checkpoint_path.exists() {
+            return Ok(None);
+        }
+
+        let checkpoint: Checkpoint = confy::load_path(&checkpoint_path)
+            .map_err(|e| format!("Unable to read {}: {}", checkpoint_path.display(), e))?;
+
+        let moves_path = dir.join("job.moves");
+        let moves = fs::read_to_string(&moves_path)
+            .map_err(|e| format!("Unable to read {}: {}", moves_path.display(), e))
+            .and_then(|text| decode_moves(&text))?;
+
+        if moves.len() != checkpoint.total_moves {
+            return Err(format!(
+                "{} has {} moves, but the job has {}",
+                moves_path.display(),
+                moves.len(),
+                checkpoint.total_moves
+            ));
+        }
+
+        Ok(Some(Job {
+            checkpoint,
+            moves,
+            dir: dir.to_path_buf(),
+        }))
+    }
+
+    /// Checks that the source is still the one the job was planned from
+    pub fn check_source(&self) -> Result<(), String> {
+        let (Some(source), Some(source_hash)) =
+            (&self.checkpoint.source, &self.checkpoint.source_hash)
+        else {
+            return Ok(());
+        };
+
+        if hash_file(source)? != *source_hash {
+            return Err(format!(
+                "{} has changed since the job started",
+                source.display()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Records that the Blot has acknowledged the first `acked` moves, and saves the checkpoint
+    pub fn record_ack(&mut self, acked: usize) -> Result<(), String> {
+        let checkpoint = &mut self.checkpoint;
+
+        for m in &self.moves[checkpoint.acked_moves.min(acked)..acked] {
+            match *m {
+                Move::PenUp => checkpoint.pen_down = false,
+                Move::PenDown => checkpoint.pen_down = true,
+                Move::Go(x, y) => checkpoint.position = (x, y),
+            }
+        }
+        checkpoint.acked_moves = acked;
+
+        self.save()
+    }
+
+    /// Removes the job, once it's done
+    pub fn finish(self) -> Result<(), String> {
+        for path in [self.checkpoint_path(), self.moves_path()] {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Unable to remove {}: {}", path.display(), e))?;
+        }
+
+        Ok(())
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = self.checkpoint_path();
+        let temp_path = path.with_extension("toml.tmp");
+
+        confy::store_path(&temp_path, &self.checkpoint)
+            .map_err(|e| format!("Unable to save {}: {}", temp_path.display(), e))?;
+        // Renaming over the old checkpoint means a crash can't leave half of one behind
+        fs::rename(&temp_path, &path)
+            .map_err(|e| format!("Unable to save {}: {}", path.display(), e))
+    }
+
+    fn checkpoint_path(&self) -> PathBuf {
+        self.dir.join("job.toml")
+    }
+
+    fn moves_path(&self) -> PathBuf {
+        self.dir.join("job.moves")
+    }
+}
+
+fn write_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    let temp_path = path.with_extension("tmp");
+
+    fs::write(&temp_path, contents)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|e| format!("Unable to save {}: {}", path.display(), e))
+}
+
+fn hash_file(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    Ok(format!("{:016x}", hash))
+}
+
+// One move per line: `up`, `down` or `go x y`
+fn encode_moves(moves: &[Move]) -> String {
+    moves
+        .iter()
+        .map(|m| match m {
+            Move::PenUp => "up\n".to_string(),
+            Move::PenDown => "down\n".to_string(),
+            Move::Go(x, y) => format!("go {} {}\n", x, y),
+        })
+        .collect()
+}
+
+fn decode_moves(text: &str) -> Result<Vec<Move>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+
+            match parts.as_slice() {
+                ["up"] => Ok(Move::PenUp),
+                ["down"] => Ok(Move::PenDown),
+                ["go", x, y] => match (x.parse(), y.parse()) {
+                    (Ok(x), Ok(y)) => Ok(Move::Go(x, y)),
+                    _ => Err(format!("Invalid coordinates on line {}: {}", i + 1, line)),
+                },
+                _ => Err(format!("Invalid move on line {}: {}", i + 1, line)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh directory for each test's job
+    fn job_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("blot-job-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn checkpoint_survives_a_reload() {
+        let dir = job_dir();
+        let source = dir.join("drawing.svg");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&source, "<svg/>").unwrap();
+        let moves = vec![
+            Move::PenUp,
+            Move::Go(1.5, 2.0),
+            Move::PenDown,
+            Move::Go(-3.0, 4.25),
+        ];
+
+        let mut job = Job::start(&dir, Some(&source), (5.0, 6.0), moves.clone()).unwrap();
+        assert_eq!(job.checkpoint.position, (5.0, 6.0));
+        job.record_ack(3).unwrap();
+
+        let loaded = Job::load(&dir).unwrap().unwrap();
+        assert_eq!(loaded.moves, moves);
+        assert_eq!(loaded.checkpoint.origin, (5.0, 6.0));
+        assert_eq!(loaded.checkpoint.acked_moves, 3);
+        assert_eq!(loaded.checkpoint.position, (1.5, 2.0));
+        assert!(loaded.checkpoint.pen_down);
+        assert!(loaded.check_source().is_ok());
+
+        fs::write(&source, "<svg></svg>").unwrap();
+        assert!(loaded.check_source().is_err());
+
+        loaded.finish().unwrap();
+        assert!(Job::load(&dir).unwrap().is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn job_without_source_always_matches() {
+        let dir = job_dir();
+
+        let job = Job::start(&dir, None, (0.0, 0.0), vec![Move::PenUp]).unwrap();
+
+        assert!(job.checkpoint.source_hash.is_none());
+        assert!(job.check_source().is_ok());
+        job.finish().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn bad_moves_file_is_reported_with_its_line() {
+        assert_eq!(
+            decode_moves("up\ngo 1 x\n").unwrap_err(),
+            "Invalid coordinates on line 2: go 1 x"
+        );
+        assert_eq!(
+            decode_moves("up\nsideways\n").unwrap_err(),
+            "Invalid move on line 2: sideways"
+        );
+    }
+}
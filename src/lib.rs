@@ -0,0 +1,28 @@
+//! Drives a Hack Club Blot over serial, TCP or a Unix socket, and turns drawings into moves for it
+
+pub mod bed;
+pub mod capture;
+pub mod client;
+pub mod comms;
+pub mod error;
+pub mod estimate;
+mod font;
+pub mod framing;
+pub mod gcode;
+pub mod hpgl;
+pub mod job;
+pub mod optimize;
+pub mod plot;
+pub mod preview;
+pub mod raster;
+pub mod script;
+pub mod shape;
+pub mod sim;
+pub mod svg;
+pub mod text;
+pub mod transform;
+pub mod transport;
+
+pub use client::{BlotClient, PenPositions};
+pub use comms::{CommsOptions, LinkState};
+pub use error::BlotError;
@@ -1,8 +1,19 @@
-mod comms;
-
-use clap::{Parser, Subcommand};
-use comms::{BlotPacket, PacketState};
-use confy;
+mod console;
+
+use blot::bed::{Bed, OutOfBounds};
+use blot::capture::{Capture, CaptureEvent};
+use blot::estimate::{self, MotionModel};
+use blot::framing::{FrameDecoder, Framing};
+use blot::job::Job;
+use blot::plot::{self, LoadOptions, Move, Point, Polyline};
+use blot::raster::{self, Raster};
+use blot::shape::Shape;
+use blot::text::{self, Align, TextOptions};
+use blot::transform::{Fit, Transform};
+use blot::{
+    comms, optimize, preview, script, BlotClient, BlotError, CommsOptions, LinkState, PenPositions,
+};
+use clap::{Args, Parser, Subcommand};
 use crossterm::{
     event::{self, DisableMouseCapture, Event as CEvent, KeyCode, KeyModifiers},
     execute,
@@ -10,35 +21,45 @@ use crossterm::{
 };
 use futures::{task::noop_waker_ref, FutureExt};
 use inquire::{self, Confirm, Select};
-use ringbuffer::{AllocRingBuffer, RingBuffer};
 use serde::{Deserialize, Serialize};
-use serialport::{self, SerialPortType};
+use serialport::SerialPortType;
 use std::{
+    collections::{HashSet, VecDeque},
     future::Future,
     io::{self, Stdout},
     panic,
+    path::{Path, PathBuf},
     pin::Pin,
     process,
-    sync::{mpsc, Arc},
+    sync::mpsc,
     task::{Context, Poll},
     thread,
     time::{Duration, Instant},
 };
-use tokio::{self, sync::Mutex};
 use tui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Span, Spans},
-    widgets::{Block, BorderType, Borders, Paragraph, Tabs},
+    widgets::{
+        canvas::{Canvas, Line, Points, Rectangle},
+        Block, BorderType, Borders, Paragraph, Tabs,
+    },
     Terminal,
 };
-use uuid::Uuid;
 
 #[derive(Serialize, Deserialize)]
 struct BlotConfig {
     port: Option<String>,
     interactive: InteractiveConfig,
+    // Configs from before the bed was configurable don't have it
+    #[serde(default)]
+    bed: BedConfig,
+    #[serde(default)]
+    pen: PenPositions,
+    #[serde(default)]
+    motion: MotionModel,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -46,15 +67,78 @@ struct InteractiveConfig {
     step: f32,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct BedConfig {
+    width: f32,
+    height: f32,
+    out_of_bounds: OutOfBounds,
+}
+
 impl ::std::default::Default for BlotConfig {
     fn default() -> Self {
         Self {
             port: None,
             interactive: InteractiveConfig { step: 5_f32 },
+            bed: BedConfig::default(),
+            pen: PenPositions::default(),
+            motion: MotionModel::default(),
         }
     }
 }
 
+impl ::std::default::Default for BedConfig {
+    fn default() -> Self {
+        let bed = Bed::default();
+
+        Self {
+            width: bed.width,
+            height: bed.height,
+            out_of_bounds: OutOfBounds::default(),
+        }
+    }
+}
+
+impl BlotConfig {
+    // Catches values that would make later calculations fail, such as a bed with no area
+    fn validate(&self) -> Result<(), String> {
+        self.bed.validate()?;
+        self.motion.validate()
+    }
+}
+
+impl BedConfig {
+    fn validate(&self) -> Result<(), String> {
+        self.bed().validate()
+    }
+
+    fn bed(&self) -> Bed {
+        Bed {
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+// Prints what the library logs as it happens, without breaking up progress lines
+struct ConsoleLogger;
+
+impl log::Log for ConsoleLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            console::message(record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: ConsoleLogger = ConsoleLogger;
+
 /// CLI for the Hack Club Blot
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -64,6 +148,26 @@ struct Cli {
 
     #[arg(short, long)]
     port: Option<String>,
+
+    /// Seconds to wait for the Blot to acknowledge each command
+    #[arg(long, default_value = "10", value_parser = parse_timeout)]
+    ack_timeout: Duration,
+
+    /// Times to resend a command the Blot didn't acknowledge before giving up
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u8).range(..=comms::MAX_RETRIES as i64))]
+    retries: u8,
+
+    /// Most commands to send ahead before waiting for the Blot to acknowledge them
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(1..=8))]
+    window: u8,
+
+    /// Seconds to wait for a disconnected Blot to come back, or 0 to not wait
+    #[arg(long, default_value = "60", value_parser = parse_seconds)]
+    reconnect_timeout: Duration,
+
+    /// Record every packet sent to and received from the Blot to a JSON Lines file
+    #[arg(long, value_name = "FILE")]
+    capture: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -92,6 +196,187 @@ enum Commands {
     },
     /// Enter interactive mode
     Interactive,
+    /// Plot an SVG, G-code or HPGL file
+    Plot {
+        #[command(flatten)]
+        drawing: DrawingArgs,
+    },
+    /// Render what plotting a file would draw to an SVG or PNG image
+    Preview {
+        #[command(flatten)]
+        drawing: DrawingArgs,
+        /// Path to write the preview to, ending in .svg or .png
+        #[arg(short, long)]
+        out: PathBuf,
+    },
+    /// Work out how long plotting a file would take, and how far the pen would go
+    Estimate {
+        #[command(flatten)]
+        drawing: DrawingArgs,
+        /// Time a few round trips to the Blot rather than using the configured ack latency
+        #[arg(long)]
+        measure: bool,
+    },
+    /// Run a Blot editor JavaScript program and plot what it draws
+    Run {
+        /// Path to the program
+        file: PathBuf,
+        /// Draw paths in the order the program drew them
+        #[arg(long)]
+        no_optimize: bool,
+        #[command(flatten)]
+        transform: TransformArgs,
+    },
+    /// Turn a PNG or JPEG image into lines and plot them
+    Image {
+        /// Path to the image
+        file: PathBuf,
+        /// How to draw it: hatch, crosshatch, stipple-path or spiral
+        #[arg(long, default_value = "hatch")]
+        style: raster::Style,
+        /// Distance in mm between lines where the image is darkest
+        #[arg(long, default_value_t = 1.0)]
+        spacing: f32,
+        /// Draw lines in the order they were traced
+        #[arg(long)]
+        no_optimize: bool,
+        #[command(flatten)]
+        transform: TransformArgs,
+    },
+    /// Plot text in a single-stroke font
+    Text {
+        /// The text to plot, with \n to start a new line
+        text: String,
+        /// Height of capital letters in mm
+        #[arg(short, long, default_value_t = 5.0, value_parser = parse_positive)]
+        size: f32,
+        /// Where the baseline of the first line is anchored, as x,y in mm
+        #[arg(long, default_value = "0,0", value_parser = parse_point, allow_hyphen_values = true)]
+        at: Point,
+        /// Built-in font to use: futural (sans serif), timesr (serif) or scripts (script)
+        #[arg(
+            long,
+            default_value = "futural",
+            value_parser = clap::builder::PossibleValuesParser::new(text::FONT_NAMES)
+        )]
+        font: String,
+        /// Width in mm to wrap lines at between words
+        #[arg(long)]
+        width: Option<f32>,
+        /// Whether lines start, are centred on or end at the anchor: left, center or right
+        #[arg(long, default_value = "left")]
+        align: Align,
+        /// Distance between baselines, as a multiple of the size
+        #[arg(long, default_value_t = 1.5)]
+        line_spacing: f32,
+        #[command(flatten)]
+        transform: TransformArgs,
+    },
+    /// Draw a shape from its measurements in mm
+    Draw {
+        #[command(subcommand)]
+        shape: DrawSubcommands,
+        /// Maximum distance in mm between a curve and the lines approximating it
+        #[arg(short, long, default_value_t = 0.1, global = true, value_parser = parse_positive)]
+        tolerance: f32,
+        #[command(flatten)]
+        transform: TransformArgs,
+    },
+    /// Carry on with a plot that was interrupted
+    Resume {
+        /// Resume even if the file being plotted has changed since the plot started
+        #[arg(long)]
+        force: bool,
+    },
+    /// Send the packets from a capture to the Blot again
+    Replay {
+        /// Path to a capture made with --capture
+        file: PathBuf,
+    },
+    /// Decode the packets in a raw dump of bytes sent over the wire
+    Decode {
+        /// Path to the dump
+        file: PathBuf,
+        /// Decode replies from the Blot, which end in a newline, instead of COBS packets sent
+        /// to it
+        #[arg(long)]
+        replies: bool,
+    },
+}
+
+#[derive(Args)]
+struct DrawingArgs {
+    /// Path to the file to plot
+    file: PathBuf,
+    /// Maximum distance in mm between a curve and the lines approximating it
+    #[arg(short, long, default_value_t = 0.1, value_parser = parse_positive)]
+    tolerance: f32,
+    /// HPGL plotter units per mm
+    #[arg(long, default_value_t = 40.0, value_parser = parse_positive)]
+    hpgl_units: f32,
+    /// Draw paths in the order they appear in the file
+    #[arg(long)]
+    no_optimize: bool,
+    #[command(flatten)]
+    transform: TransformArgs,
+}
+
+// Global so that they can come after the shape given to `blot draw`
+#[derive(Args, Clone)]
+struct TransformArgs {
+    /// Flip the drawing left to right
+    #[arg(long, global = true)]
+    mirror_x: bool,
+    /// Flip the drawing top to bottom
+    #[arg(long, global = true)]
+    mirror_y: bool,
+    /// Factor to scale the drawing by
+    #[arg(long, default_value_t = 1.0, global = true)]
+    scale: f32,
+    /// Degrees to rotate the drawing counterclockwise. Text turns around its --at point
+    /// instead, before any other changes
+    #[arg(
+        long,
+        default_value_t = 0.0,
+        allow_negative_numbers = true,
+        global = true
+    )]
+    rotate: f32,
+    /// Resize the drawing to fit the bed, width or height, and centre it on the bed
+    #[arg(long, global = true)]
+    fit: Option<Fit>,
+    /// Distance to move the drawing, as x,y in mm
+    #[arg(long, default_value = "0,0", value_parser = parse_point, allow_hyphen_values = true, global = true)]
+    translate: Point,
+}
+
+impl TransformArgs {
+    fn transform(&self) -> Result<Transform, String> {
+        if !(self.scale.is_finite() && self.scale > 0.0) {
+            return Err(format!("The scale must be more than 0, not {}", self.scale));
+        }
+        if !self.rotate.is_finite() {
+            return Err(format!(
+                "The rotation must be a number of degrees, not {}",
+                self.rotate
+            ));
+        }
+        if !(self.translate.0.is_finite() && self.translate.1.is_finite()) {
+            return Err(format!(
+                "The distance to move must be a number of mm, not {},{}",
+                self.translate.0, self.translate.1
+            ));
+        }
+
+        Ok(Transform {
+            mirror_x: self.mirror_x,
+            mirror_y: self.mirror_y,
+            scale: self.scale,
+            rotate: self.rotate,
+            fit: self.fit,
+            translate: self.translate,
+        })
+    }
 }
 
 #[derive(Subcommand)]
@@ -116,6 +401,120 @@ enum PenSubcommands {
     Up,
     /// Move the pen down
     Down,
+    /// Lower the pen step by step to find where it just touches the paper, and save that
+    Calibrate,
+}
+
+#[derive(Subcommand)]
+enum DrawSubcommands {
+    /// Draw a straight line
+    Line {
+        /// Where the line starts, as x,y
+        #[arg(value_parser = parse_point, allow_hyphen_values = true)]
+        from: Point,
+        /// Where the line ends, as x,y
+        #[arg(value_parser = parse_point, allow_hyphen_values = true)]
+        to: Point,
+    },
+    /// Draw an upright rectangle
+    Rect {
+        /// The bottom left corner, as x,y
+        #[arg(value_parser = parse_point, allow_hyphen_values = true)]
+        corner: Point,
+        width: f32,
+        height: f32,
+    },
+    /// Draw a circle
+    Circle {
+        /// The centre, as x,y
+        #[arg(value_parser = parse_point, allow_hyphen_values = true)]
+        center: Point,
+        radius: f32,
+    },
+    /// Draw an ellipse with its axes along x and y
+    Ellipse {
+        /// The centre, as x,y
+        #[arg(value_parser = parse_point, allow_hyphen_values = true)]
+        center: Point,
+        x_radius: f32,
+        y_radius: f32,
+    },
+    /// Draw part of a circle
+    Arc {
+        /// The centre, as x,y
+        #[arg(value_parser = parse_point, allow_hyphen_values = true)]
+        center: Point,
+        radius: f32,
+        /// Where the arc starts, in degrees counterclockwise from the positive x axis
+        #[arg(allow_negative_numbers = true)]
+        start: f32,
+        /// Degrees to sweep through, counterclockwise or clockwise if negative
+        #[arg(allow_negative_numbers = true)]
+        sweep: f32,
+    },
+    /// Draw a closed polygon
+    Polygon {
+        // Hyphen values let points be negative, but mean anything after them is taken as a
+        // point too, so options have to come before the shape
+        /// The corners in order, each as x,y
+        #[arg(required = true, num_args = 3.., value_parser = parse_listed_point, allow_hyphen_values = true)]
+        corners: Vec<Point>,
+    },
+    /// Draw a quadratic or cubic Bézier curve
+    Bezier {
+        /// The start, one or two control points and the end, each as x,y
+        #[arg(required = true, num_args = 3..=4, value_parser = parse_listed_point, allow_hyphen_values = true)]
+        points: Vec<Point>,
+    },
+}
+
+impl DrawSubcommands {
+    fn shape(&self) -> Shape {
+        match *self {
+            DrawSubcommands::Line { from, to } => Shape::Line { from, to },
+            DrawSubcommands::Rect {
+                corner,
+                width,
+                height,
+            } => Shape::Rect {
+                corner,
+                width,
+                height,
+            },
+            DrawSubcommands::Circle { center, radius } => Shape::Circle { center, radius },
+            DrawSubcommands::Ellipse {
+                center,
+                x_radius,
+                y_radius,
+            } => Shape::Ellipse {
+                center,
+                x_radius,
+                y_radius,
+            },
+            DrawSubcommands::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => Shape::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            },
+            DrawSubcommands::Polygon { ref corners } => Shape::Polygon(corners.clone()),
+            DrawSubcommands::Bezier { ref points } => Shape::Bezier(points.clone()),
+        }
+    }
+}
+
+// A command sent from interactive mode, resolving once the Blot acknowledges it
+type CommandFuture<'a> = Pin<Box<dyn Future<Output = Result<InteractiveUpdate, BlotError>> + 'a>>;
+
+// What changed once the Blot acknowledged an interactive command
+enum InteractiveUpdate {
+    Moved(InteractiveCoordinates),
+    Pen(InteractivePenStatus),
 }
 
 enum Event<I> {
@@ -148,6 +547,14 @@ enum InteractivePosStatus {
     Initializing,
     Moving(InteractiveDestination),
     Stopped,
+    Failed(String),
+}
+
+// A move made during an interactive session, drawn on the bed canvas
+struct InteractiveSegment {
+    from: (f32, f32),
+    to: (f32, f32),
+    pen_down: bool,
 }
 
 enum InteractivePenStatus {
@@ -165,27 +572,78 @@ enum InteractiveEditStatus {
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
-    let cfg: Result<BlotConfig, confy::ConfyError> = confy::load("blot-cli", "blot");
-    let cfg_port = match cfg {
-        Ok(config) => config.port,
-        Err(_) => None,
+
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+
+    let cfg: BlotConfig = match confy::load("blot-cli", "blot") {
+        Ok(config) => config,
+        Err(e) => {
+            println!("Unable to load the config, so using the defaults: {e}");
+            BlotConfig::default()
+        }
     };
-    let port = match cfg_port {
+    if let Err(e) = cfg.validate() {
+        println!("Invalid config: {e}");
+        process::exit(1);
+    }
+
+    // Previews don't need a Blot, so they're made before looking for one
+    if let Commands::Preview { drawing, out } = &cli.command {
+        let moves = load_moves(drawing, &cfg.bed);
+
+        if let Err(e) = preview::render(&moves, &cfg.bed.bed(), out) {
+            println!("{e}");
+            process::exit(1);
+        }
+
+        println!(
+            "Saved preview of {} to {}",
+            drawing.file.display(),
+            out.display()
+        );
+        return;
+    }
+
+    // Nor do estimates, unless the latency is measured
+    if let Commands::Estimate {
+        drawing,
+        measure: false,
+    } = &cli.command
+    {
+        print_estimate(drawing, &cfg, cfg.motion.ack_latency, cli.window as usize);
+        return;
+    }
+
+    // Neither do dumps
+    if let Commands::Decode { file, replies } = &cli.command {
+        let framing = if *replies {
+            Framing::Line
+        } else {
+            Framing::Cobs
+        };
+
+        if let Err(e) = decode(file, framing) {
+            println!("{e}");
+            process::exit(1);
+        }
+        return;
+    }
+
+    let port = match cli.port {
         Some(p) => p,
-        None => match cli.port {
+        None => match cfg.port.clone() {
             Some(p) => p,
             None => {
-                let ports = serialport::available_ports().unwrap_or(vec![]);
+                let ports = serialport::available_ports().unwrap_or_default();
 
                 let filtered = ports
                     .iter()
-                    .filter(|p| match p.port_type {
-                        SerialPortType::UsbPort(_) => true,
-                        _ => false,
-                    })
+                    .filter(|p| matches!(p.port_type, SerialPortType::UsbPort(_)))
                     .collect::<Vec<_>>();
 
-                if filtered.len() == 0 {
+                if filtered.is_empty() {
                     println!("No USB serial ports available on system. Make sure the Blot is powered on and plugged in via USB.");
                     process::exit(1);
                 }
@@ -214,29 +672,21 @@ async fn main() {
                         .with_default(true)
                         .prompt();
 
-                        match ans {
-                            Ok(save) => {
-                                if save {
-                                    let current_cfg: BlotConfig =
-                                        confy::load("blot-cli", "blot").unwrap_or_default();
-                                    let save_result = confy::store(
-                                        "blot-cli",
-                                        "blot",
-                                        BlotConfig {
-                                            port: Some(choice.clone()),
-                                            interactive: current_cfg.interactive,
-                                        },
-                                    );
+                        if let Ok(true) = ans {
+                            let current_cfg: BlotConfig =
+                                confy::load("blot-cli", "blot").unwrap_or_default();
+                            let save_result = confy::store(
+                                "blot-cli",
+                                "blot",
+                                BlotConfig {
+                                    port: Some(choice.clone()),
+                                    ..current_cfg
+                                },
+                            );
 
-                                    if save_result.is_err() {
-                                        println!(
-                                            "Unable to save config: {}",
-                                            save_result.unwrap_err()
-                                        );
-                                    }
-                                };
+                            if let Err(e) = save_result {
+                                println!("Unable to save config: {}", e);
                             }
-                            Err(_) => (),
                         }
 
                         choice
@@ -250,65 +700,268 @@ async fn main() {
         },
     };
 
-    let packet_queue = Arc::new(Mutex::new(AllocRingBuffer::new(10)));
-    let comms_thread = tokio::spawn(comms::initialize(port, packet_queue.clone()));
-
-    // Exit main thread if comms thread panics
-    let orig_hook = panic::take_hook();
-    panic::set_hook(Box::new(move |panic_info| {
-        orig_hook(panic_info);
-        process::exit(1);
-    }));
+    let options = CommsOptions {
+        ack_timeout: cli.ack_timeout,
+        retries: cli.retries,
+        window: cli.window as usize,
+        reconnect_timeout: Some(cli.reconnect_timeout).filter(|t| !t.is_zero()),
+        capture: cli
+            .capture
+            .as_deref()
+            .map(|path| match Capture::create(path) {
+                Ok(capture) => capture,
+                Err(e) => {
+                    println!("{e}");
+                    process::exit(1);
+                }
+            }),
+    };
+    let mut client = exit_on_error(BlotClient::connect(&port, options));
+    client.set_pen_positions(cfg.pen);
 
     match &cli.command {
         Commands::Go { x, y } => {
-            println!("Going to: ({}, {})", x, y);
-            send_command(
-                packet_queue,
-                "go",
-                [x.to_le_bytes(), y.to_le_bytes()].concat(),
-            )
-            .await;
+            // Where the pen is now isn't known, so clipping clamps
+            let (new_x, new_y) = exit_on_error(cfg.bed.bed().limit_move(
+                None,
+                (*x, *y),
+                cfg.bed.out_of_bounds,
+            ));
+
+            if (new_x, new_y) != (*x, *y) {
+                println!("({}, {}) is off the bed, so going as far as it can", x, y);
+            }
+            println!("Going to: ({}, {})", new_x, new_y);
+            exit_on_error(client.go(new_x, new_y).await);
         }
         Commands::Motors { cmd } => match cmd {
             MotorsSubcommands::On => {
                 println!("Turning stepper motors on");
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                send_command(packet_queue.clone(), "motorsOn", vec![]).await;
+                for _ in 0..10 {
+                    exit_on_error(client.motors_on().await);
+                }
             }
             MotorsSubcommands::Off => {
                 println!("Turning stepper motors off");
-                send_command(packet_queue, "motorsOff", vec![]).await;
+                exit_on_error(client.motors_off().await);
             }
         },
         Commands::Origin { cmd } => match cmd {
             OriginSubcommands::Move => {
                 println!("Moving towards origin");
-                send_command(packet_queue, "moveTowardsOrigin", vec![]).await;
+                exit_on_error(client.move_towards_origin().await);
             }
             OriginSubcommands::Set => {
                 println!("Setting origin");
-                send_command(packet_queue, "setOrigin", vec![]).await;
+                exit_on_error(client.set_origin().await);
             }
         },
         Commands::Pen { cmd } => match cmd {
             PenSubcommands::Up => {
                 println!("Moving pen up");
-                send_command(packet_queue, "servo", 1000_u32.to_le_bytes().to_vec()).await;
+                exit_on_error(client.pen_up().await);
             }
             PenSubcommands::Down => {
                 println!("Moving pen down");
-                send_command(packet_queue, "servo", 1700_u32.to_le_bytes().to_vec()).await;
+                exit_on_error(client.pen_down().await);
+            }
+            PenSubcommands::Calibrate => {
+                let Some(down) = exit_on_error(calibrate_pen(&client).await) else {
+                    println!(
+                        "Cancelled, so the pen down position is still {}",
+                        cfg.pen.down
+                    );
+                    return;
+                };
+
+                let current_cfg: BlotConfig = confy::load("blot-cli", "blot").unwrap_or_default();
+                let pen = PenPositions {
+                    down,
+                    ..current_cfg.pen
+                };
+                let save_result =
+                    confy::store("blot-cli", "blot", BlotConfig { pen, ..current_cfg });
+
+                match save_result {
+                    Ok(()) => println!("Saved {} as the pen down position", down),
+                    Err(e) => println!("Unable to save config: {}", e),
+                }
             }
         },
+        Commands::Plot { drawing } => {
+            let moves = load_moves(drawing, &cfg.bed);
+
+            println!("Plotting {}", drawing.file.display());
+            let job = start_job(Some(&drawing.file), &moves);
+            exit_on_error(send_moves(&client, &moves, job).await);
+        }
+        // Previews and dumps are dealt with before connecting to the Blot
+        Commands::Preview { .. } | Commands::Decode { .. } => {}
+        Commands::Estimate { drawing, .. } => {
+            println!("Measuring the ack latency");
+            let latency = exit_on_error(measure_latency(&client).await);
+            print_estimate(drawing, &cfg, latency, cli.window as usize);
+        }
+        Commands::Run {
+            file,
+            no_optimize,
+            transform,
+        } => {
+            let polylines = match std::fs::read_to_string(file)
+                .map_err(|e| format!("Unable to read {}: {}", file.display(), e))
+                .and_then(|source| script::run(&source))
+                .and_then(|polylines| place(polylines, transform, &cfg.bed))
+            {
+                Ok(p) => p,
+                Err(e) => {
+                    println!("{e}");
+                    process::exit(1);
+                }
+            };
+
+            println!("Plotting {}", file.display());
+            let moves = plan_moves(polylines, !*no_optimize);
+            let job = start_job(Some(file), &moves);
+            exit_on_error(send_moves(&client, &moves, job).await);
+        }
+        Commands::Image {
+            file,
+            style,
+            spacing,
+            no_optimize,
+            transform,
+        } => {
+            let bed = cfg.bed.bed();
+            let image = match Raster::load(file, &bed) {
+                Ok(image) => image,
+                Err(e) => {
+                    println!("{e}");
+                    process::exit(1);
+                }
+            };
+
+            let (width, height) = image.size();
+            println!(
+                "Plotting {} at {:.1}×{:.1} mm",
+                file.display(),
+                width,
+                height
+            );
+            let polylines = match place(image.trace(*style, *spacing), transform, &cfg.bed) {
+                Ok(p) => p,
+                Err(e) => {
+                    println!("{e}");
+                    process::exit(1);
+                }
+            };
+            let moves = plan_moves(polylines, !*no_optimize);
+            let job = start_job(Some(file), &moves);
+            exit_on_error(send_moves(&client, &moves, job).await);
+        }
+        Commands::Text {
+            text,
+            size,
+            at,
+            font,
+            width,
+            align,
+            line_spacing,
+            transform,
+        } => {
+            // Text turns around its anchor, as it did before --rotate applied to everything
+            let options = TextOptions {
+                font: font.clone(),
+                size: *size,
+                at: *at,
+                wrap: *width,
+                align: *align,
+                rotate: transform.rotate,
+                line_spacing: *line_spacing,
+            };
+            let transform = TransformArgs {
+                rotate: 0.0,
+                ..transform.clone()
+            };
+
+            let polylines = match text::layout(&text.replace("\\n", "\n"), &options)
+                .and_then(|polylines| place(polylines, &transform, &cfg.bed))
+            {
+                Ok(p) => p,
+                Err(e) => {
+                    println!("{e}");
+                    process::exit(1);
+                }
+            };
+
+            println!("Plotting \"{}\"", text);
+            let moves = plan_moves(polylines, true);
+            let job = start_job(None, &moves);
+            exit_on_error(send_moves(&client, &moves, job).await);
+        }
+        Commands::Draw {
+            shape,
+            tolerance,
+            transform,
+        } => {
+            let polylines = match shape
+                .shape()
+                .trace(*tolerance)
+                .and_then(|polyline| place(vec![polyline], transform, &cfg.bed))
+            {
+                Ok(p) => p,
+                Err(e) => {
+                    println!("{e}");
+                    process::exit(1);
+                }
+            };
+
+            let moves = plan_moves(polylines, false);
+            let job = start_job(None, &moves);
+            exit_on_error(send_moves(&client, &moves, job).await);
+        }
+        Commands::Resume { force } => {
+            let job = match Job::load(&job_dir()) {
+                Ok(Some(job)) => job,
+                Ok(None) => {
+                    println!("There's no interrupted plot to resume");
+                    process::exit(1);
+                }
+                Err(e) => {
+                    println!("{e}");
+                    process::exit(1);
+                }
+            };
+
+            if let Err(e) = job.check_source() {
+                if !*force {
+                    println!("{e}. Pass --force to resume it anyway");
+                    process::exit(1);
+                }
+            }
+
+            match &job.checkpoint.source {
+                Some(source) => println!("Plotting {}", source.display()),
+                None => println!("Resuming the interrupted plot"),
+            }
+            let moves = job.moves.clone();
+            exit_on_error(send_moves(&client, &moves, Some(job)).await);
+        }
+        Commands::Replay { file } => {
+            let packets = match load_replay(file) {
+                Ok(packets) => packets,
+                Err(e) => {
+                    println!("{e}");
+                    process::exit(1);
+                }
+            };
+
+            println!(
+                "Replaying {} packets from {}",
+                packets.len(),
+                file.display()
+            );
+            exit_on_error(replay(&client, &packets, cli.window as usize).await);
+        }
         Commands::Interactive => {
             let orig_hook = panic::take_hook();
             panic::set_hook(Box::new(move |panic_info| {
@@ -335,10 +988,8 @@ async fn main() {
                         }
                     }
 
-                    if last_tick.elapsed() >= tick_rate {
-                        if let Ok(_) = tx.send(Event::Tick) {
-                            last_tick = Instant::now();
-                        }
+                    if last_tick.elapsed() >= tick_rate && tx.send(Event::Tick).is_ok() {
+                        last_tick = Instant::now();
                     }
                 }
             });
@@ -366,48 +1017,57 @@ async fn main() {
             let mut interactive_edit_status = InteractiveEditStatus::None;
 
             let mut edit_text = "".to_string();
+            let mut session_segments: Vec<InteractiveSegment> = vec![];
 
-            let current_cfg: BlotConfig = confy::load("blot-cli", "blot").unwrap_or_default();
-            let mut step_size = current_cfg.interactive.step;
+            let mut step_size = cfg.interactive.step;
 
-            let mut pending_futures: Vec<Pin<Box<dyn Future<Output = BlotPacket>>>> = vec![];
+            let mut pending_futures: Vec<CommandFuture<'_>> = vec![];
+            let mut link_state = LinkState::Connected;
 
             let mut ctx = Context::from_waker(noop_waker_ref());
             loop {
                 pending_futures = pending_futures
                     .into_iter()
                     .filter_map(|mut future| {
-                        let res = (&mut future).poll_unpin(&mut ctx);
+                        let res = future.poll_unpin(&mut ctx);
 
                         match res {
-                            Poll::Ready(p) => match p.msg.as_str() {
-                                "go" => {
-                                    interactive_pos_status = InteractivePosStatus::Stopped;
-                                    interactive_coordinates = InteractiveCoordinates {
-                                        x: f32::from_le_bytes(p.payload[0..4].try_into().unwrap()),
-                                        y: f32::from_le_bytes(p.payload[4..8].try_into().unwrap()),
-                                    };
-
-                                    None
-                                }
-                                "servo" => {
-                                    let servo_position =
-                                        u32::from_le_bytes(p.payload[0..4].try_into().unwrap());
-                                    interactive_pen_status = if servo_position == 1700 {
+                            Poll::Ready(Err(e)) => {
+                                interactive_pos_status =
+                                    InteractivePosStatus::Failed(e.to_string());
+
+                                None
+                            }
+                            Poll::Ready(Ok(InteractiveUpdate::Moved(new_coordinates))) => {
+                                interactive_pos_status = InteractivePosStatus::Stopped;
+                                session_segments.push(InteractiveSegment {
+                                    from: (interactive_coordinates.x, interactive_coordinates.y),
+                                    to: (new_coordinates.x, new_coordinates.y),
+                                    pen_down: matches!(
+                                        interactive_pen_status,
                                         InteractivePenStatus::Down
-                                    } else {
-                                        InteractivePenStatus::Up
-                                    };
+                                    ),
+                                });
+                                interactive_coordinates = new_coordinates;
 
-                                    None
-                                }
-                                _ => None,
-                            },
+                                None
+                            }
+                            Poll::Ready(Ok(InteractiveUpdate::Pen(pen_status))) => {
+                                interactive_pen_status = pen_status;
+
+                                None
+                            }
                             Poll::Pending => Some(future),
                         }
                     })
                     .collect();
 
+                // Connection events are printed over the TUI, so redraw it from scratch after one
+                if client.link_state() != link_state {
+                    link_state = client.link_state();
+                    terminal.clear().expect("Failed to clear terminal");
+                }
+
                 terminal
                     .draw(|f| {
                         let main_chunks = Layout::default()
@@ -486,12 +1146,17 @@ async fn main() {
                                 "Blot is stopped at ({}, {})",
                                 interactive_coordinates.x, interactive_coordinates.y
                             ),
+                            InteractivePosStatus::Failed(e) => e,
                         };
                         let pen_text = match &interactive_pen_status {
                             InteractivePenStatus::Down => "Pen is DOWN",
                             InteractivePenStatus::Up => "Pen is UP",
                         };
-                        let status_text = format!("{pos_text}\n{pen_text}");
+                        let status_text = if link_state == LinkState::Reconnecting {
+                            format!("Waiting for the Blot to reconnect\n{pen_text}")
+                        } else {
+                            format!("{pos_text}\n{pen_text}")
+                        };
 
                         let blot_status = Paragraph::new(status_text)
                             .style(Style::default().fg(Color::LightGreen))
@@ -521,22 +1186,73 @@ async fn main() {
                                     .border_type(BorderType::Plain),
                             );
 
+                        // Keep the bed square, as terminal cells are about twice as tall as wide
+                        let bed_width = main_chunks[2].width.min(main_chunks[2].height * 2);
+                        let bed_area = Rect {
+                            x: main_chunks[2].x + (main_chunks[2].width - bed_width) / 2,
+                            width: bed_width,
+                            ..main_chunks[2]
+                        };
+
+                        let bed = Canvas::default()
+                            .block(Block::default().title("Bed").borders(Borders::ALL))
+                            .marker(Marker::Braille)
+                            .x_bounds([0.0, cfg.bed.width as f64])
+                            .y_bounds([0.0, cfg.bed.height as f64])
+                            .paint(|ctx| {
+                                ctx.draw(&Rectangle {
+                                    x: 0.0,
+                                    y: 0.0,
+                                    width: cfg.bed.width as f64,
+                                    height: cfg.bed.height as f64,
+                                    color: Color::DarkGray,
+                                });
+
+                                for segment in &session_segments {
+                                    ctx.draw(&Line {
+                                        x1: segment.from.0 as f64,
+                                        y1: segment.from.1 as f64,
+                                        x2: segment.to.0 as f64,
+                                        y2: segment.to.1 as f64,
+                                        color: if segment.pen_down {
+                                            Color::LightCyan
+                                        } else {
+                                            Color::Red
+                                        },
+                                    });
+                                }
+
+                                ctx.layer();
+                                ctx.draw(&Points {
+                                    coords: &[(
+                                        interactive_coordinates.x as f64,
+                                        interactive_coordinates.y as f64,
+                                    )],
+                                    color: Color::Yellow,
+                                });
+                            });
+
                         f.render_widget(info, main_chunks[0]);
                         f.render_widget(blot_status, status_chunks[0]);
                         f.render_widget(input_box, status_chunks[1]);
+                        f.render_widget(bed, bed_area);
                         f.render_widget(tabs, main_chunks[3]);
                     })
                     .expect("Failed to draw tui");
 
                 if interactive_pos_status == InteractivePosStatus::Initializing {
-                    send_command(
-                        packet_queue.clone(),
-                        "servo",
-                        1000_u32.to_le_bytes().to_vec(),
-                    )
+                    let initialized = async {
+                        client.pen_up().await?;
+                        client.motors_on().await?;
+                        client.go(0.0, 0.0).await
+                    }
                     .await;
-                    send_command(packet_queue.clone(), "motorsOn", vec![]).await;
-                    send_command(packet_queue.clone(), "go", vec![0, 0, 0, 0, 0, 0, 0, 0]).await;
+
+                    if let Err(e) = initialized {
+                        restore_terminal(terminal);
+                        println!("{e}");
+                        process::exit(e.exit_code());
+                    }
                     interactive_pos_status = InteractivePosStatus::Stopped;
                 }
 
@@ -544,11 +1260,11 @@ async fn main() {
                     match rx.recv() {
                         Ok(Event::Input(event)) => {
                             match event.code {
-                                KeyCode::Char('c') => {
-                                    if event.modifiers.contains(KeyModifiers::CONTROL) {
-                                        restore_terminal(terminal);
-                                        break;
-                                    }
+                                KeyCode::Char('c')
+                                    if event.modifiers.contains(KeyModifiers::CONTROL) =>
+                                {
+                                    restore_terminal(terminal);
+                                    break;
                                 }
                                 KeyCode::Char(c) => {
                                     let num_parse = c.to_string().parse::<f32>();
@@ -566,7 +1282,7 @@ async fn main() {
                                 KeyCode::Enter => {
                                     match &interactive_edit_status {
                                         InteractiveEditStatus::GoCoordinates => {
-                                            let split = edit_text.split(",").collect::<Vec<_>>();
+                                            let split = edit_text.split(',').collect::<Vec<_>>();
                                             let x_parse = split[0].trim().parse::<f32>();
                                             let y_parse = split[1].trim().parse::<f32>();
 
@@ -574,27 +1290,17 @@ async fn main() {
                                                 continue;
                                             }
 
-                                            let mut new_x = x_parse.unwrap();
-                                            let mut new_y = y_parse.unwrap();
-
-                                            if new_y < 0.0 {
-                                                new_y = 0.0;
-                                            }
-                                            if new_y > 125.0 {
-                                                new_y = 125.0;
-                                            }
-                                            if new_x < 0.0 {
-                                                new_x = 0.0;
-                                            }
-                                            if new_x > 125.0 {
-                                                new_x = 125.0;
-                                            }
+                                            let Some((new_x, new_y)) = interactive_target(
+                                                &cfg.bed,
+                                                &interactive_coordinates,
+                                                (x_parse.unwrap(), y_parse.unwrap()),
+                                                &mut interactive_pos_status,
+                                            ) else {
+                                                continue;
+                                            };
 
-                                            let command_future = send_command(
-                                                packet_queue.clone(),
-                                                "go",
-                                                [new_x.to_le_bytes(), new_y.to_le_bytes()].concat(),
-                                            );
+                                            let command_future =
+                                                interactive_go(&client, new_x, new_y);
                                             interactive_pos_status = InteractivePosStatus::Moving(
                                                 InteractiveDestination::Coordinates(
                                                     InteractiveCoordinates { x: new_x, y: new_y },
@@ -611,7 +1317,10 @@ async fn main() {
 
                                             let new_step_size = step_parse.unwrap();
 
-                                            if (new_step_size <= 0.0) | (new_step_size >= 125.0) {
+                                            if (new_step_size <= 0.0)
+                                                | (new_step_size
+                                                    >= cfg.bed.width.max(cfg.bed.height))
+                                            {
                                                 continue;
                                             }
 
@@ -623,10 +1332,10 @@ async fn main() {
                                                 "blot-cli",
                                                 "blot",
                                                 BlotConfig {
-                                                    port: current_cfg.port,
                                                     interactive: InteractiveConfig {
                                                         step: step_size,
                                                     },
+                                                    ..current_cfg
                                                 },
                                             );
                                         }
@@ -659,19 +1368,18 @@ async fn main() {
                                 interactive_edit_status = InteractiveEditStatus::StepSize;
                             }
                             KeyCode::Char('f') | KeyCode::Char('w') => {
-                                let mut new_y = interactive_coordinates.y + step_size;
-                                if new_y < 0.0 {
-                                    new_y = 0.0;
-                                }
-                                if new_y > 125.0 {
-                                    new_y = 125.0;
-                                }
-                                let command_future = send_command(
-                                    packet_queue.clone(),
-                                    "go",
-                                    [interactive_coordinates.x.to_le_bytes(), new_y.to_le_bytes()]
-                                        .concat(),
-                                );
+                                let Some((new_x, new_y)) = interactive_target(
+                                    &cfg.bed,
+                                    &interactive_coordinates,
+                                    (
+                                        interactive_coordinates.x,
+                                        interactive_coordinates.y + step_size,
+                                    ),
+                                    &mut interactive_pos_status,
+                                ) else {
+                                    continue;
+                                };
+                                let command_future = interactive_go(&client, new_x, new_y);
                                 interactive_pos_status = InteractivePosStatus::Moving(
                                     InteractiveDestination::Direction(
                                         InteractiveDirection::Forward,
@@ -680,77 +1388,66 @@ async fn main() {
                                 pending_futures.push(Box::pin(command_future));
                             }
                             KeyCode::Char('a') | KeyCode::Char('l') => {
-                                let mut new_x = interactive_coordinates.x - step_size;
-                                if new_x < 0.0 {
-                                    new_x = 0.0;
-                                }
-                                if new_x > 125.0 {
-                                    new_x = 125.0;
-                                }
-                                let command_future = send_command(
-                                    packet_queue.clone(),
-                                    "go",
-                                    [new_x.to_le_bytes(), interactive_coordinates.y.to_le_bytes()]
-                                        .concat(),
-                                );
+                                let Some((new_x, new_y)) = interactive_target(
+                                    &cfg.bed,
+                                    &interactive_coordinates,
+                                    (
+                                        interactive_coordinates.x - step_size,
+                                        interactive_coordinates.y,
+                                    ),
+                                    &mut interactive_pos_status,
+                                ) else {
+                                    continue;
+                                };
+                                let command_future = interactive_go(&client, new_x, new_y);
                                 interactive_pos_status = InteractivePosStatus::Moving(
                                     InteractiveDestination::Direction(InteractiveDirection::Left),
                                 );
                                 pending_futures.push(Box::pin(command_future));
                             }
                             KeyCode::Char('b') | KeyCode::Char('s') => {
-                                let mut new_y = interactive_coordinates.y - step_size;
-                                if new_y < 0.0 {
-                                    new_y = 0.0;
-                                }
-                                if new_y > 125.0 {
-                                    new_y = 125.0;
-                                }
-                                let command_future = send_command(
-                                    packet_queue.clone(),
-                                    "go",
-                                    [interactive_coordinates.x.to_le_bytes(), new_y.to_le_bytes()]
-                                        .concat(),
-                                );
+                                let Some((new_x, new_y)) = interactive_target(
+                                    &cfg.bed,
+                                    &interactive_coordinates,
+                                    (
+                                        interactive_coordinates.x,
+                                        interactive_coordinates.y - step_size,
+                                    ),
+                                    &mut interactive_pos_status,
+                                ) else {
+                                    continue;
+                                };
+                                let command_future = interactive_go(&client, new_x, new_y);
                                 interactive_pos_status = InteractivePosStatus::Moving(
                                     InteractiveDestination::Direction(InteractiveDirection::Back),
                                 );
                                 pending_futures.push(Box::pin(command_future));
                             }
                             KeyCode::Char('r') | KeyCode::Char('d') => {
-                                let mut new_x = interactive_coordinates.x + step_size;
-                                if new_x < 0.0 {
-                                    new_x = 0.0;
-                                }
-                                if new_x > 125.0 {
-                                    new_x = 125.0;
-                                }
-                                let command_future = send_command(
-                                    packet_queue.clone(),
-                                    "go",
-                                    [new_x.to_le_bytes(), interactive_coordinates.y.to_le_bytes()]
-                                        .concat(),
-                                );
+                                let Some((new_x, new_y)) = interactive_target(
+                                    &cfg.bed,
+                                    &interactive_coordinates,
+                                    (
+                                        interactive_coordinates.x + step_size,
+                                        interactive_coordinates.y,
+                                    ),
+                                    &mut interactive_pos_status,
+                                ) else {
+                                    continue;
+                                };
+                                let command_future = interactive_go(&client, new_x, new_y);
                                 interactive_pos_status = InteractivePosStatus::Moving(
                                     InteractiveDestination::Direction(InteractiveDirection::Right),
                                 );
                                 pending_futures.push(Box::pin(command_future));
                             }
                             KeyCode::Char('u') | KeyCode::Up => {
-                                let command_future = send_command(
-                                    packet_queue.clone(),
-                                    "servo",
-                                    1000_u32.to_le_bytes().to_vec(),
-                                );
+                                let command_future = interactive_pen(&client, false);
                                 interactive_pen_status = InteractivePenStatus::Up;
                                 pending_futures.push(Box::pin(command_future));
                             }
                             KeyCode::Char('p') | KeyCode::Down => {
-                                let command_future = send_command(
-                                    packet_queue.clone(),
-                                    "servo",
-                                    1700_u32.to_le_bytes().to_vec(),
-                                );
+                                let command_future = interactive_pen(&client, true);
                                 interactive_pen_status = InteractivePenStatus::Down;
                                 pending_futures.push(Box::pin(command_future));
                             }
@@ -764,7 +1461,7 @@ async fn main() {
         }
     }
 
-    comms_thread.abort();
+    client.close().await;
 }
 
 fn restore_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) {
@@ -779,42 +1476,426 @@ fn restore_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) {
     terminal.show_cursor().expect("Failed to restore terminal");
 }
 
-async fn send_command(
-    packet_queue: Arc<Mutex<AllocRingBuffer<BlotPacket>>>,
-    msg: &str,
-    payload: Vec<u8>,
-) -> BlotPacket {
-    let mut packets = packet_queue.lock().await;
-
-    let id = Uuid::new_v4();
-    let packet = BlotPacket {
-        id,
-        msg: msg.to_string(),
-        payload,
-        index: None,
-        state: comms::PacketState::Queued,
+// Moves the pen from interactive mode, reporting where it ended up
+async fn interactive_go(
+    client: &BlotClient,
+    x: f32,
+    y: f32,
+) -> Result<InteractiveUpdate, BlotError> {
+    client.go(x, y).await?;
+
+    Ok(InteractiveUpdate::Moved(InteractiveCoordinates { x, y }))
+}
+
+// Keeps a move from interactive mode on the bed, or shows why it can't be made
+fn interactive_target(
+    bed: &BedConfig,
+    from: &InteractiveCoordinates,
+    to: (f32, f32),
+    status: &mut InteractivePosStatus,
+) -> Option<(f32, f32)> {
+    match bed
+        .bed()
+        .limit_move(Some((from.x, from.y)), to, bed.out_of_bounds)
+    {
+        Ok(target) => Some(target),
+        Err(e) => {
+            *status = InteractivePosStatus::Failed(e.to_string());
+            None
+        }
+    }
+}
+
+// Lifts or lowers the pen from interactive mode, reporting its new state
+async fn interactive_pen(client: &BlotClient, down: bool) -> Result<InteractiveUpdate, BlotError> {
+    if down {
+        client.pen_down().await?;
+        Ok(InteractiveUpdate::Pen(InteractivePenStatus::Down))
+    } else {
+        client.pen_up().await?;
+        Ok(InteractiveUpdate::Pen(InteractivePenStatus::Up))
+    }
+}
+
+// Servo steps the pen can be nudged by while calibrating, smallest first
+const CALIBRATION_STEPS: [u32; 4] = [1, 5, 20, 50];
+
+// Lets the user nudge the pen down from its up position until it touches the paper, and
+// returns the servo position they settled on, or `None` if they cancelled. The pen is lifted
+// again afterwards.
+async fn calibrate_pen(client: &BlotClient) -> Result<Option<u32>, BlotError> {
+    let pen = client.pen_positions();
+    // Servos can be mounted either way round, so lowering the pen can count up or down
+    let lowering = pen.down >= pen.up;
+    let mut position = pen.up;
+    let mut step = 2;
+
+    println!("Put paper under the pen, then lower it until it just touches the paper.");
+    println!("  Down/Up or j/k: lower or raise the pen");
+    println!("  Left/Right or h/l: take smaller or bigger steps");
+    println!("  Enter: save the position, Esc or q: cancel");
+
+    client.servo(position).await?;
+    enable_raw_mode().expect("failed to enable raw mode");
+
+    let chosen = loop {
+        console::progress(format!(
+            "Servo at {:<5} step {:<3} (saved pen down position: {})",
+            position, CALIBRATION_STEPS[step], pen.down
+        ));
+
+        let Ok(CEvent::Key(key)) = event::read() else {
+            continue;
+        };
+        let lower = match key.code {
+            KeyCode::Down | KeyCode::Char('j') => true,
+            KeyCode::Up | KeyCode::Char('k') => false,
+            KeyCode::Left | KeyCode::Char('h') => {
+                step = step.saturating_sub(1);
+                continue;
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                step = (step + 1).min(CALIBRATION_STEPS.len() - 1);
+                continue;
+            }
+            KeyCode::Enter => break Some(position),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break None,
+            KeyCode::Esc | KeyCode::Char('q') => break None,
+            _ => continue,
+        };
+
+        position = if lower == lowering {
+            position.saturating_add(CALIBRATION_STEPS[step])
+        } else {
+            position.saturating_sub(CALIBRATION_STEPS[step])
+        };
+
+        if let Err(e) = client.servo(position).await {
+            disable_raw_mode().expect("Failed to restore terminal");
+            return Err(e);
+        }
     };
-    packets.push(packet.clone());
 
-    // Drop mutex so comms thread can gain a lock
-    std::mem::drop(packets);
-    wait_for_ack(packet_queue, id).await;
+    disable_raw_mode().expect("Failed to restore terminal");
+    console::finish_progress();
+    client.servo(pen.up).await?;
 
-    packet
+    Ok(chosen)
 }
 
-async fn wait_for_ack(packet_queue: Arc<Mutex<AllocRingBuffer<BlotPacket>>>, id: Uuid) {
-    loop {
-        let packets = packet_queue.lock().await;
+// Prints the error and exits with its code, for commands that can't go on without the Blot
+fn exit_on_error<T>(result: Result<T, BlotError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            console::message(&e);
+            process::exit(e.exit_code());
+        }
+    }
+}
 
-        let packet_result = packets.iter().find(|p| p.id == id);
+// Loads a drawing, brings it within the bed and plans the moves to plot it, exiting if it
+// can't be loaded
+fn load_moves(drawing: &DrawingArgs, bed: &BedConfig) -> Vec<Move> {
+    let options = LoadOptions {
+        tolerance: drawing.tolerance,
+        hpgl_units: drawing.hpgl_units,
+    };
 
-        if let Some(packet) = packet_result {
-            if packet.state == PacketState::Resolved {
-                break;
+    match plot::load(&drawing.file, &options)
+        .and_then(|polylines| place(polylines, &drawing.transform, bed))
+    {
+        Ok(polylines) => plan_moves(polylines, !drawing.no_optimize),
+        Err(e) => {
+            println!("{e}");
+            process::exit(1);
+        }
+    }
+}
+
+// Round trips timed to measure the ack latency
+const LATENCY_SAMPLES: usize = 5;
+
+// Times pen-up commands, which don't change anything once the first has lifted the pen, and
+// returns the median round trip in seconds
+async fn measure_latency(client: &BlotClient) -> Result<f32, BlotError> {
+    let mut samples = Vec::with_capacity(LATENCY_SAMPLES);
+
+    for _ in 0..LATENCY_SAMPLES {
+        let start = Instant::now();
+        client.pen_up().await?;
+        samples.push(start.elapsed().as_secs_f32());
+    }
+    samples.sort_by(f32::total_cmp);
+
+    Ok(samples[LATENCY_SAMPLES / 2])
+}
+
+fn print_estimate(drawing: &DrawingArgs, cfg: &BlotConfig, ack_latency: f32, window: usize) {
+    let moves = load_moves(drawing, &cfg.bed);
+    let model = &cfg.motion;
+    let estimate = estimate::estimate(&moves, ORIGIN, model, ack_latency, window);
+
+    println!("Pen-down distance: {:.1} mm", estimate.pen_down_distance);
+    println!("Pen-up travel: {:.1} mm", estimate.pen_up_travel);
+    println!("Pen lifts: {}", estimate.pen_lifts);
+    println!("Packets: {}", estimate.packets);
+    println!(
+        "Estimated time: {} (at {} mm/s, {} s for the servo and {:.0} ms ack latency)",
+        format_duration(estimate.duration),
+        model.feed_rate,
+        model.servo_settle,
+        ack_latency * 1000.0
+    );
+}
+
+// Formats a duration as hours, minutes and seconds, leaving out leading zero units
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f32().round() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+// Moves, turns and resizes a drawing as asked, then brings it within the bed
+fn place(
+    polylines: Vec<Polyline>,
+    transform: &TransformArgs,
+    bed: &BedConfig,
+) -> Result<Vec<Polyline>, String> {
+    let bed_area = bed.bed();
+    let polylines = transform.transform()?.apply(polylines, &bed_area);
+
+    bed_area
+        .limit(polylines, bed.out_of_bounds)
+        .map_err(|e| e.to_string())
+}
+
+// Orders the drawing for the least pen-up travel, unless asked not to, and turns it into moves
+fn plan_moves(polylines: Vec<Polyline>, optimize: bool) -> Vec<Move> {
+    let polylines = if optimize {
+        let before = optimize::travel(&polylines, ORIGIN);
+        let polylines = optimize::optimize(polylines, ORIGIN);
+        let after = optimize::travel(&polylines, ORIGIN);
+
+        println!(
+            "Reordered paths to cut pen-up travel from {:.1} mm to {:.1} mm",
+            before, after
+        );
+        polylines
+    } else {
+        polylines
+    };
+
+    plot::moves(&polylines)
+}
+
+// Parses a point given as x,y
+fn parse_point(s: &str) -> Result<Point, String> {
+    let parsed = s
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .filter(|(x, y): &Point| x.is_finite() && y.is_finite());
+
+    parsed.ok_or_else(|| format!("expected x,y in mm, not {}", s))
+}
+
+// Parses one of a list of points, which options can't follow
+fn parse_listed_point(s: &str) -> Result<Point, String> {
+    parse_point(s).map_err(|e| {
+        if s.starts_with('-') || s.parse::<f32>().is_ok() {
+            format!(
+                "{}. Options go before the shape when it's given a list of points",
+                e
+            )
+        } else {
+            e
+        }
+    })
+}
+
+// Where plots start from, which moves are planned from
+const ORIGIN: Point = (0.0, 0.0);
+
+// Jobs are kept next to the config file
+fn job_dir() -> PathBuf {
+    match confy::get_configuration_file_path("blot-cli", "blot") {
+        Ok(path) => path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        Err(e) => {
+            println!("Unable to find the config directory: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+// Saves the moves for a new plot so it can be resumed. Plots go ahead without that if the
+// job can't be saved.
+fn start_job(source: Option<&Path>, moves: &[Move]) -> Option<Job> {
+    match Job::start(&job_dir(), source, ORIGIN, moves.to_vec()) {
+        Ok(job) => Some(job),
+        Err(e) => {
+            println!("{e}, so this plot can't be resumed if it's interrupted");
+            None
+        }
+    }
+}
+
+// Sends moves, carrying on from where the job got to, and checkpoints the job after each ack
+async fn send_moves(
+    client: &BlotClient,
+    moves: &[Move],
+    mut job: Option<Job>,
+) -> Result<(), BlotError> {
+    let start = job.as_ref().map_or(0, |job| job.checkpoint.acked_moves);
+    let mut checkpoint_failed = false;
+
+    client.motors_on().await?;
+
+    client
+        .send_moves_from(moves, start, |acked| {
+            console::progress(format!("Sent {}/{} moves", acked, moves.len()));
+
+            if let Some(job) = &mut job {
+                if let Err(e) = job.record_ack(acked) {
+                    // Only say so once, rather than after every move
+                    if !checkpoint_failed {
+                        console::message(format!("{e}, so this plot can't be resumed"));
+                        checkpoint_failed = true;
+                    }
+                }
+            }
+        })
+        .await?;
+
+    console::finish_progress();
+
+    if let Some(job) = job {
+        if let Err(e) = job.finish() {
+            console::message(e);
+        }
+    }
+
+    let dropped = client.dropped_frames();
+    if dropped > 0 {
+        println!("Skipped {} malformed replies from the Blot", dropped);
+    }
+
+    Ok(())
+}
+
+// The packets a capture sent, in the order they were first sent. Resends are left out, as the
+// client resends anything the Blot doesn't acknowledge by itself.
+fn load_replay(path: &Path) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut seen = HashSet::new();
+
+    Capture::load(path)?
+        .into_iter()
+        .filter(|record| record.event == CaptureEvent::Sent && seen.insert(record.id.clone()))
+        .map(|record| Ok((record.msg.clone(), record.payload_bytes()?)))
+        .collect()
+}
+
+// Sends packets in order, keeping up to a window's worth of them waiting for acks at once
+async fn replay(
+    client: &BlotClient,
+    packets: &[(String, Vec<u8>)],
+    window: usize,
+) -> Result<(), BlotError> {
+    let mut unacked = VecDeque::new();
+    let mut acked = 0;
+
+    let mut queued = 0;
+
+    while acked < packets.len() {
+        if queued < packets.len() && unacked.len() < window {
+            let (msg, payload) = &packets[queued];
+            unacked.push_back(client.queue(msg, payload.clone()).await?);
+            queued += 1;
+            continue;
+        }
+
+        client.wait(unacked.pop_front().unwrap()).await?;
+        acked += 1;
+        console::progress(format!("Sent {}/{} packets", acked, packets.len()));
+    }
+
+    console::finish_progress();
+
+    Ok(())
+}
+
+// Prints each packet in a dump, along with anything that couldn't be decoded
+fn decode(path: &Path, framing: Framing) -> Result<(), String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+
+    let mut decoder = FrameDecoder::new(framing);
+    decoder.push(&bytes);
+
+    let mut frames = 0;
+    loop {
+        match decoder.next_frame() {
+            Some(Ok(packet)) => {
+                frames += 1;
+                let index = packet.index.unwrap_or_default();
+                println!("{:>5}  index {}  {}", frames, index, packet);
             }
+            Some(Err(e)) => {
+                frames += 1;
+                println!("{:>5}  {}", frames, e);
+            }
+            // A frame that never finished could still have whole frames after it
+            None => match decoder.resync() {
+                Some(e) => {
+                    frames += 1;
+                    println!("{:>5}  {}", frames, e);
+                }
+                None => break,
+            },
         }
+    }
+
+    println!(
+        "Decoded {} frames, {} of them malformed",
+        frames,
+        decoder.dropped()
+    );
+    if decoder.pending() > 0 {
+        println!(
+            "The last {} bytes didn't make a whole packet",
+            decoder.pending()
+        );
+    }
+
+    Ok(())
+}
 
-        tokio::time::sleep(Duration::from_millis(10)).await;
+// Parses a number that has to be more than 0
+fn parse_positive(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(n) if n.is_finite() && n > 0.0 => Ok(n),
+        _ => Err(format!("expected a number more than 0, not {}", s)),
     }
 }
+
+// Parses a number of seconds, which can be 0 but not less
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    s.parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f32(seconds).ok())
+        .ok_or_else(|| format!("expected a number of seconds that's 0 or more, not {}", s))
+}
+
+// Parses a number of seconds to wait before giving up, which has to be more than 0
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    parse_seconds(s)
+        .ok()
+        .filter(|timeout| !timeout.is_zero())
+        .ok_or_else(|| format!("expected a number of seconds more than 0, not {}", s))
+}
@@ -0,0 +1,206 @@
+use std::time::{Duration, Instant};
+
+use crate::plot::{self, Point, Polyline};
+
+// How long 2-opt may keep looking for improvements on very large drawings
+const IMPROVE_TIME_LIMIT: Duration = Duration::from_secs(5);
+
+/// Total distance travelled with the pen up when drawing polylines in order from `start`
+pub fn travel(polylines: &[Polyline], start: Point) -> f32 {
+    let mut position = start;
+    let mut total = 0.0;
+
+    for polyline in polylines.iter().filter(|p| !p.is_empty()) {
+        total += plot::distance(position, polyline[0]);
+        position = polyline[polyline.len() - 1];
+    }
+
+    total
+}
+
+/// Reorders and reverses polylines to shorten pen-up travel
+///
+/// A nearest-neighbour pass picks a starting order, and 2-opt then reverses runs of
+/// polylines until no reversal makes the travel any shorter.
+pub fn optimize(polylines: Vec<Polyline>, start: Point) -> Vec<Polyline> {
+    let polylines = nearest_neighbour(polylines, start);
+
+    two_opt(polylines, start)
+}
+
+fn nearest_neighbour(mut remaining: Vec<Polyline>, start: Point) -> Vec<Polyline> {
+    remaining.retain(|p| !p.is_empty());
+
+    let mut ordered = Vec::with_capacity(remaining.len());
+    let mut position = start;
+
+    while !remaining.is_empty() {
+        let mut best = (0, false, f32::INFINITY);
+
+        for (i, polyline) in remaining.iter().enumerate() {
+            let to_start = plot::distance(position, polyline[0]);
+            let to_end = plot::distance(position, polyline[polyline.len() - 1]);
+
+            if to_start < best.2 {
+                best = (i, false, to_start);
+            }
+            if to_end < best.2 {
+                best = (i, true, to_end);
+            }
+        }
+
+        let mut polyline = remaining.swap_remove(best.0);
+        if best.1 {
+            polyline.reverse();
+        }
+
+        position = polyline[polyline.len() - 1];
+        ordered.push(polyline);
+    }
+
+    ordered
+}
+
+fn two_opt(mut polylines: Vec<Polyline>, start: Point) -> Vec<Polyline> {
+    let deadline = Instant::now() + IMPROVE_TIME_LIMIT;
+    let n = polylines.len();
+
+    // Only the end points matter, so work on those and reorder the polylines once at the end
+    let mut order: Vec<(usize, bool)> = (0..n).map(|i| (i, false)).collect();
+    let ends: Vec<(Point, Point)> = polylines.iter().map(|p| (p[0], p[p.len() - 1])).collect();
+    let entry = |&(i, reversed): &(usize, bool)| if reversed { ends[i].1 } else { ends[i].0 };
+    let exit = |&(i, reversed): &(usize, bool)| if reversed { ends[i].0 } else { ends[i].1 };
+
+    let mut improved = true;
+    while improved && Instant::now() < deadline {
+        improved = false;
+
+        for i in 0..n {
+            let before = if i == 0 { start } else { exit(&order[i - 1]) };
+
+            for j in i + 1..n {
+                // Reversing i..=j swaps which end of the run joins onto its neighbours
+                let mut delta = plot::distance(before, exit(&order[j]))
+                    - plot::distance(before, entry(&order[i]));
+                if j + 1 < n {
+                    let after = entry(&order[j + 1]);
+                    delta += plot::distance(entry(&order[i]), after)
+                        - plot::distance(exit(&order[j]), after);
+                }
+
+                if delta < -0.001 {
+                    order[i..=j].reverse();
+                    for step in &mut order[i..=j] {
+                        step.1 = !step.1;
+                    }
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<Polyline>> = polylines.drain(..).map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|(i, reversed)| {
+            let mut polyline = slots[i].take()?;
+            if reversed {
+                polyline.reverse();
+            }
+            Some(polyline)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Short horizontal strokes scattered over a 10×10 grid in a fixed, jumbled order
+    fn scattered() -> Vec<Polyline> {
+        (0..100)
+            .map(|i| {
+                let cell = (i * 37) % 100;
+                let (x, y) = ((cell % 10) as f32 * 10.0, (cell / 10) as f32 * 10.0);
+                if i % 2 == 0 {
+                    vec![(x, y), (x + 2.0, y)]
+                } else {
+                    vec![(x + 2.0, y), (x, y)]
+                }
+            })
+            .collect()
+    }
+
+    // Sorts each polyline's ends so that reversed polylines compare equal
+    fn normalized(polylines: &[Polyline]) -> Vec<(Point, Point)> {
+        let mut ends: Vec<(Point, Point)> = polylines
+            .iter()
+            .map(|p| {
+                let (a, b) = (p[0], p[p.len() - 1]);
+                if a.partial_cmp(&b) == Some(std::cmp::Ordering::Greater) {
+                    (b, a)
+                } else {
+                    (a, b)
+                }
+            })
+            .collect();
+        ends.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        ends
+    }
+
+    #[test]
+    fn travel_goes_from_each_end_to_the_next_start() {
+        let polylines = vec![vec![(0.0, 3.0), (10.0, 3.0)], vec![], vec![(10.0, 7.0)]];
+
+        assert_eq!(travel(&polylines, (0.0, 7.0)), 4.0 + 4.0);
+        assert_eq!(travel(&[], (0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn reverses_a_polyline_whose_far_end_is_closer() {
+        let polylines = vec![vec![(10.0, 0.0), (1.0, 0.0)]];
+
+        let optimized = optimize(polylines, (0.0, 0.0));
+
+        assert_eq!(optimized, vec![vec![(1.0, 0.0), (10.0, 0.0)]]);
+    }
+
+    #[test]
+    fn keeps_every_polyline_and_shortens_travel() {
+        let polylines = scattered();
+        let before = travel(&polylines, (0.0, 0.0));
+
+        let optimized = optimize(polylines.clone(), (0.0, 0.0));
+        let after = travel(&optimized, (0.0, 0.0));
+
+        assert_eq!(normalized(&optimized), normalized(&polylines));
+        assert!(after < before / 4.0, "{} -> {}", before, after);
+    }
+
+    #[test]
+    fn two_opt_undoes_a_crossing() {
+        // Nearest neighbour alone would leave this order, whose travel crosses itself
+        let polylines = vec![
+            vec![(1.0, 0.0), (2.0, 0.0)],
+            vec![(2.0, 5.0), (3.0, 5.0)],
+            vec![(3.0, 1.0), (4.0, 1.0)],
+            vec![(4.0, 6.0), (5.0, 6.0)],
+        ];
+        let before = travel(&polylines, (0.0, 0.0));
+
+        let improved = two_opt(polylines, (0.0, 0.0));
+
+        assert!(travel(&improved, (0.0, 0.0)) < before);
+    }
+
+    #[test]
+    fn empty_polylines_are_dropped() {
+        let optimized = optimize(
+            vec![vec![], vec![(1.0, 1.0), (2.0, 2.0)], vec![]],
+            (0.0, 0.0),
+        );
+
+        assert_eq!(optimized, vec![vec![(1.0, 1.0), (2.0, 2.0)]]);
+        assert!(optimize(vec![], (0.0, 0.0)).is_empty());
+    }
+}
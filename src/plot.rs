@@ -0,0 +1,259 @@
+use std::path::Path;
+
+use crate::{gcode, hpgl, script, svg};
+
+/// A point on the Blot's bed, in millimetres
+pub type Point = (f32, f32);
+
+/// A run of points drawn with the pen down
+pub type Polyline = Vec<Point>;
+
+/// A single step sent to the Blot while plotting
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Move {
+    PenUp,
+    PenDown,
+    Go(f32, f32),
+}
+
+/// A 2D affine transform, mapping (x, y) to (a*x + c*y + e, b*x + d*y + f)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Affine {
+    pub fn new(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Affine {
+        Affine { a, b, c, d, e, f }
+    }
+
+    /// Returns the transform that applies `self` and then `next`
+    pub fn then(&self, next: &Affine) -> Affine {
+        Affine {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    pub fn apply(&self, (x, y): Point) -> Point {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+}
+
+/// Follows the pen around, collecting the polylines it draws
+pub struct Tracer {
+    pub position: Point,
+    pub pen_down: bool,
+    current: Polyline,
+    polylines: Vec<Polyline>,
+}
+
+impl Tracer {
+    pub fn new() -> Tracer {
+        Tracer {
+            position: (0.0, 0.0),
+            pen_down: false,
+            current: vec![],
+            polylines: vec![],
+        }
+    }
+
+    pub fn set_pen(&mut self, down: bool) {
+        if down && !self.pen_down {
+            self.current = vec![self.position];
+        } else if !down && self.pen_down {
+            self.polylines.push(std::mem::take(&mut self.current));
+        }
+
+        self.pen_down = down;
+    }
+
+    pub fn line_to(&mut self, target: Point) {
+        self.position = target;
+
+        if self.pen_down {
+            self.current.push(target);
+        }
+    }
+
+    /// Lifts the pen and returns everything that was drawn
+    pub fn finish(mut self) -> Vec<Polyline> {
+        self.set_pen(false);
+
+        self.polylines.into_iter().filter(|p| p.len() > 1).collect()
+    }
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Settings for turning a file into polylines
+pub struct LoadOptions {
+    /// Maximum distance in mm between a curve and the lines approximating it
+    pub tolerance: f32,
+    /// HPGL plotter units per millimetre
+    pub hpgl_units: f32,
+}
+
+/// Loads a drawing from a file, picking the format from its extension
+pub fn load(path: &Path, options: &LoadOptions) -> Result<Vec<Polyline>, String> {
+    if !(options.tolerance.is_finite() && options.tolerance > 0.0) {
+        return Err(format!(
+            "The tolerance must be more than 0 mm, not {}",
+            options.tolerance
+        ));
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+
+    match extension.as_str() {
+        "svg" => svg::parse(&source, options.tolerance),
+        "gcode" | "gc" | "g" | "nc" | "ngc" => gcode::parse(&source, options.tolerance),
+        "hpgl" | "hpg" | "hgl" | "plt" => {
+            hpgl::parse(&source, options.hpgl_units, options.tolerance)
+        }
+        "js" => script::run(&source),
+        _ => Err(format!("Unsupported file type: {}", path.display())),
+    }
+}
+
+/// Turns polylines into the moves needed to draw them, lifting the pen between each one
+pub fn moves(polylines: &[Polyline]) -> Vec<Move> {
+    let mut moves = vec![Move::PenUp];
+
+    for polyline in polylines.iter().filter(|p| !p.is_empty()) {
+        let (x, y) = polyline[0];
+        moves.push(Move::Go(x, y));
+        moves.push(Move::PenDown);
+
+        for &(x, y) in &polyline[1..] {
+            moves.push(Move::Go(x, y));
+        }
+
+        moves.push(Move::PenUp);
+    }
+
+    moves
+}
+
+/// Appends points approximating a quadratic Bézier curve, excluding its start point
+pub fn flatten_quadratic(out: &mut Polyline, p0: Point, p1: Point, p2: Point, tolerance: f32) {
+    let dd = distance(
+        (0.0, 0.0),
+        (p0.0 - 2.0 * p1.0 + p2.0, p0.1 - 2.0 * p1.1 + p2.1),
+    );
+    let segments = segment_count(0.25 * dd, tolerance);
+
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1.0 - t;
+        out.push((
+            mt * mt * p0.0 + 2.0 * mt * t * p1.0 + t * t * p2.0,
+            mt * mt * p0.1 + 2.0 * mt * t * p1.1 + t * t * p2.1,
+        ));
+    }
+}
+
+/// Appends points approximating a cubic Bézier curve, excluding its start point
+pub fn flatten_cubic(
+    out: &mut Polyline,
+    p0: Point,
+    p1: Point,
+    p2: Point,
+    p3: Point,
+    tolerance: f32,
+) {
+    let dd = f32::max(
+        distance(
+            (0.0, 0.0),
+            (p0.0 - 2.0 * p1.0 + p2.0, p0.1 - 2.0 * p1.1 + p2.1),
+        ),
+        distance(
+            (0.0, 0.0),
+            (p1.0 - 2.0 * p2.0 + p3.0, p1.1 - 2.0 * p2.1 + p3.1),
+        ),
+    );
+    let segments = segment_count(0.75 * dd, tolerance);
+
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1.0 - t;
+        out.push((
+            mt * mt * mt * p0.0
+                + 3.0 * mt * mt * t * p1.0
+                + 3.0 * mt * t * t * p2.0
+                + t * t * t * p3.0,
+            mt * mt * mt * p0.1
+                + 3.0 * mt * mt * t * p1.1
+                + 3.0 * mt * t * t * p2.1
+                + t * t * t * p3.1,
+        ));
+    }
+}
+
+/// Appends points approximating a circular arc, excluding its start point
+///
+/// Angles are in radians, and a positive sweep goes counterclockwise.
+pub fn flatten_arc(
+    out: &mut Polyline,
+    center: Point,
+    radius: f32,
+    start_angle: f32,
+    sweep: f32,
+    tolerance: f32,
+) {
+    // Largest step that keeps the middle of each chord within `tolerance` of the arc. A
+    // negative radius, as HPGL allows, draws the same circle starting from the other side.
+    let tolerance = tolerance.max(MIN_TOLERANCE);
+    let max_step = if radius.abs() > tolerance {
+        2.0 * (1.0 - tolerance / radius.abs()).acos()
+    } else {
+        std::f32::consts::FRAC_PI_2
+    };
+    let segments = ((sweep.abs() / max_step).ceil() as usize).clamp(1, 1000);
+
+    for i in 1..=segments {
+        let angle = start_angle + sweep * i as f32 / segments as f32;
+        out.push((
+            center.0 + radius * angle.cos(),
+            center.1 + radius * angle.sin(),
+        ));
+    }
+}
+
+pub fn distance(a: Point, b: Point) -> f32 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+// Smallest tolerance curves are traced to, so that a tiny or nonsensical one can't ask for
+// an endless number of segments
+const MIN_TOLERANCE: f32 = 0.001;
+
+// Wang's formula: the number of line segments keeping a curve within `tolerance` of its chords
+fn segment_count(deviation: f32, tolerance: f32) -> usize {
+    let segments = (deviation / tolerance.max(MIN_TOLERANCE)).sqrt().ceil();
+
+    segments.clamp(1.0, 1000.0) as usize
+}
@@ -0,0 +1,246 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use image::{ImageFormat, Rgb, RgbImage};
+
+use crate::bed::Bed;
+use crate::plot::{self, Move, Point};
+
+// Blank space around the bed and the drawing, in millimetres
+const MARGIN: f32 = 5.0;
+
+// PNG resolution, reduced for drawings that spill far off the bed
+const PX_PER_MM: f32 = 8.0;
+const MAX_PNG_SIZE: f32 = 2400.0;
+
+// Lengths of the dashes and gaps in pen-up travel, in millimetres
+const DASH: (f32, f32) = (1.5, 1.0);
+
+// Ink width, in millimetres
+const INK_WIDTH: f32 = 0.4;
+
+const PAPER: [u8; 3] = [255, 255, 255];
+const BED: [u8; 3] = [160, 160, 160];
+const TRAVEL: [u8; 3] = [220, 60, 60];
+const INK: [u8; 3] = [20, 30, 90];
+
+/// A line the pen follows, either drawing or travelling with the pen up
+struct Stroke {
+    from: Point,
+    to: Point,
+    ink: bool,
+}
+
+/// Renders moves on a bed to an SVG or PNG file, picking the format from its extension
+pub fn render(moves: &[Move], bed: &Bed, path: &Path) -> Result<(), String> {
+    let strokes = strokes(moves);
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let written = match extension.as_str() {
+        "svg" => std::fs::write(path, svg(&strokes, bed)).map_err(|e| e.to_string()),
+        "png" => png(&strokes, bed)
+            .save_with_format(path, ImageFormat::Png)
+            .map_err(|e| e.to_string()),
+        _ => {
+            return Err(format!(
+                "Previews can be .svg or .png, not {}",
+                path.display()
+            ))
+        }
+    };
+
+    written.map_err(|e| format!("Unable to write {}: {}", path.display(), e))
+}
+
+// Follows the pen through the moves, starting from the origin with the pen up
+fn strokes(moves: &[Move]) -> Vec<Stroke> {
+    let mut position = (0.0, 0.0);
+    let mut pen_down = false;
+    let mut strokes = vec![];
+
+    for m in moves {
+        match *m {
+            Move::PenUp => pen_down = false,
+            Move::PenDown => pen_down = true,
+            Move::Go(x, y) => {
+                if (x, y) != position {
+                    strokes.push(Stroke {
+                        from: position,
+                        to: (x, y),
+                        ink: pen_down,
+                    });
+                }
+                position = (x, y);
+            }
+        }
+    }
+
+    strokes
+}
+
+// The area to show: the bed, everything the pen visits and a margin around both
+fn extent(strokes: &[Stroke], bed: &Bed) -> (Point, Point) {
+    let mut min = (0.0_f32, 0.0_f32);
+    let mut max = (bed.width, bed.height);
+
+    for stroke in strokes {
+        for (x, y) in [stroke.from, stroke.to] {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+    }
+
+    (
+        (min.0 - MARGIN, min.1 - MARGIN),
+        (max.0 + MARGIN, max.1 + MARGIN),
+    )
+}
+
+fn svg(strokes: &[Stroke], bed: &Bed) -> String {
+    let (min, max) = extent(strokes, bed);
+    let (width, height) = (max.0 - min.0, max.1 - min.1);
+    let hex = |[r, g, b]: [u8; 3]| format!("#{:02x}{:02x}{:02x}", r, g, b);
+
+    let path = |ink: bool| {
+        let mut d = String::new();
+        let mut last = None;
+
+        for stroke in strokes.iter().filter(|s| s.ink == ink) {
+            if last != Some(stroke.from) {
+                let _ = write!(d, "M{} {} ", stroke.from.0, stroke.from.1);
+            }
+            let _ = write!(d, "L{} {} ", stroke.to.0, stroke.to.1);
+            last = Some(stroke.to);
+        }
+
+        d
+    };
+
+    // Flip the y axis so that the Blot's origin is at the bottom left
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}mm" height="{height}mm" viewBox="0 0 {width} {height}">
+  <rect width="100%" height="100%" fill="{paper}"/>
+  <g transform="matrix(1 0 0 -1 {tx} {ty})" fill="none" stroke-linecap="round" stroke-linejoin="round">
+    <rect x="0" y="0" width="{bed_width}" height="{bed_height}" stroke="{bed}" stroke-width="0.3"/>
+    <path d="{travel_path}" stroke="{travel}" stroke-width="0.2" stroke-dasharray="{dash} {gap}"/>
+    <path d="{ink_path}" stroke="{ink}" stroke-width="{INK_WIDTH}"/>
+  </g>
+</svg>
+"##,
+        paper = hex(PAPER),
+        bed = hex(BED),
+        bed_width = bed.width,
+        bed_height = bed.height,
+        travel = hex(TRAVEL),
+        ink = hex(INK),
+        tx = -min.0,
+        ty = max.1,
+        travel_path = path(false).trim_end(),
+        ink_path = path(true).trim_end(),
+        dash = DASH.0,
+        gap = DASH.1,
+    )
+}
+
+struct Canvas {
+    image: RgbImage,
+    scale: f32,
+    min: Point,
+    max: Point,
+}
+
+impl Canvas {
+    fn to_pixel(&self, (x, y): Point) -> Point {
+        ((x - self.min.0) * self.scale, (self.max.1 - y) * self.scale)
+    }
+
+    fn dot(&mut self, (x, y): Point, radius: f32, color: [u8; 3]) {
+        let r = radius.ceil() as i64;
+
+        for dy in -r..=r {
+            for dx in -r..=r {
+                if ((dx * dx + dy * dy) as f32) > radius * radius + 0.5 {
+                    continue;
+                }
+
+                let (px, py) = (x.round() as i64 + dx, y.round() as i64 + dy);
+                if px >= 0
+                    && py >= 0
+                    && (px as u32) < self.image.width()
+                    && (py as u32) < self.image.height()
+                {
+                    self.image.put_pixel(px as u32, py as u32, Rgb(color));
+                }
+            }
+        }
+    }
+
+    // Draws a line in millimetres, optionally dashed, by stamping dots half a pixel apart
+    fn line(
+        &mut self,
+        from: Point,
+        to: Point,
+        width: f32,
+        color: [u8; 3],
+        dash: Option<(f32, f32)>,
+    ) {
+        let length = plot::distance(from, to);
+        let steps = ((length * self.scale * 2.0).ceil() as usize).max(1);
+        let radius = (width * self.scale / 2.0).max(0.5) - 0.5;
+
+        for i in 0..=steps {
+            let t = i as f32 / steps as f32;
+
+            if let Some((on, off)) = dash {
+                if (t * length) % (on + off) > on {
+                    continue;
+                }
+            }
+
+            let point = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+            self.dot(self.to_pixel(point), radius, color);
+        }
+    }
+}
+
+fn png(strokes: &[Stroke], bed: &Bed) -> RgbImage {
+    let (min, max) = extent(strokes, bed);
+    let (width_mm, height_mm) = (max.0 - min.0, max.1 - min.1);
+    let scale = PX_PER_MM.min(MAX_PNG_SIZE / width_mm.max(height_mm));
+    let (width, height) = (
+        (width_mm * scale).ceil() as u32,
+        (height_mm * scale).ceil() as u32,
+    );
+
+    let mut canvas = Canvas {
+        image: RgbImage::from_pixel(width, height, Rgb(PAPER)),
+        scale,
+        min,
+        max,
+    };
+
+    let corners = [
+        (0.0, 0.0),
+        (bed.width, 0.0),
+        (bed.width, bed.height),
+        (0.0, bed.height),
+        (0.0, 0.0),
+    ];
+    for pair in corners.windows(2) {
+        canvas.line(pair[0], pair[1], 0.3, BED, None);
+    }
+
+    // Ink goes on last so that travel never hides what gets drawn
+    for stroke in strokes.iter().filter(|s| !s.ink) {
+        canvas.line(stroke.from, stroke.to, 0.2, TRAVEL, Some(DASH));
+    }
+    for stroke in strokes.iter().filter(|s| s.ink) {
+        canvas.line(stroke.from, stroke.to, INK_WIDTH, INK, None);
+    }
+
+    canvas.image
+}
@@ -0,0 +1,344 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, TAU};
+use std::path::Path;
+use std::str::FromStr;
+
+use image::imageops::FilterType;
+
+use crate::bed::Bed;
+use crate::plot::{self, Point, Polyline};
+
+// Resolution the image is resampled to before tracing, in pixels per millimetre
+const PX_PER_MM: f32 = 4.0;
+
+// Distance between points along traced lines, in millimetres
+const STEP: f32 = 0.25;
+
+// Anything lighter than this is left blank
+const WHITE: f32 = 0.05;
+
+/// How brightness is turned into lines
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Style {
+    /// Parallel lines, more of them where the image is darker
+    #[default]
+    Hatch,
+    /// Layers of hatching at different angles, adding a layer for each darker tone
+    Crosshatch,
+    /// One continuous line through dots scattered more densely where the image is darker
+    StipplePath,
+    /// A spiral out from the middle that wiggles further from its path where it's darker
+    Spiral,
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hatch" => Ok(Style::Hatch),
+            "crosshatch" => Ok(Style::Crosshatch),
+            "stipple-path" => Ok(Style::StipplePath),
+            "spiral" => Ok(Style::Spiral),
+            _ => Err(format!(
+                "Styles are hatch, crosshatch, stipple-path and spiral, not {}",
+                s
+            )),
+        }
+    }
+}
+
+/// A greyscale image placed on the bed, as darkness from 0 for white to 1 for black
+pub struct Raster {
+    width: usize,
+    height: usize,
+    darkness: Vec<f32>,
+    // Where the image's bottom left corner is on the bed, in millimetres
+    origin: Point,
+}
+
+impl Raster {
+    /// Loads a PNG or JPEG image, scaled to fit the bed as large as it can and centred on it
+    pub fn load(path: &Path, bed: &Bed) -> Result<Raster, String> {
+        let image =
+            image::open(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+
+        let scale = (bed.width / image.width() as f32).min(bed.height / image.height() as f32);
+        let size_mm = (image.width() as f32 * scale, image.height() as f32 * scale);
+        let width = ((size_mm.0 * PX_PER_MM).round() as u32).max(1);
+        let height = ((size_mm.1 * PX_PER_MM).round() as u32).max(1);
+
+        // Transparent pixels count as the white of the paper underneath
+        let pixels = image
+            .resize_exact(width, height, FilterType::Triangle)
+            .to_luma_alpha8();
+        let darkness = pixels
+            .pixels()
+            .map(|p| {
+                let [luma, alpha] = p.0;
+                (1.0 - luma as f32 / 255.0) * (alpha as f32 / 255.0)
+            })
+            .collect();
+
+        Ok(Raster {
+            width: width as usize,
+            height: height as usize,
+            darkness,
+            origin: (
+                (bed.width - size_mm.0) / 2.0,
+                (bed.height - size_mm.1) / 2.0,
+            ),
+        })
+    }
+
+    /// The image's size on the bed, in millimetres
+    pub fn size(&self) -> (f32, f32) {
+        (
+            self.width as f32 / PX_PER_MM,
+            self.height as f32 / PX_PER_MM,
+        )
+    }
+
+    /// How dark the image is at a point on the bed, and 0 off the image
+    pub fn darkness_at(&self, (x, y): Point) -> f32 {
+        let px = ((x - self.origin.0) * PX_PER_MM).floor();
+        // Rows go down from the top of the image, while y goes up from the bottom of the bed
+        let py = (self.height as f32 - (y - self.origin.1) * PX_PER_MM).floor();
+
+        if px < 0.0 || py < 0.0 || px >= self.width as f32 || py >= self.height as f32 {
+            return 0.0;
+        }
+
+        self.darkness[py as usize * self.width + px as usize]
+    }
+
+    /// Traces the image as lines in a style, `spacing` mm apart where it's darkest
+    pub fn trace(&self, style: Style, spacing: f32) -> Vec<Polyline> {
+        let spacing = spacing.max(STEP);
+
+        match style {
+            // Every fourth line from the lightest tones, every other one from the midtones
+            // and all of them in the shadows
+            Style::Hatch => self.hatch(0.0, spacing, |i| match i % 4 {
+                0 => WHITE,
+                2 => 0.4,
+                _ => 0.7,
+            }),
+            Style::Crosshatch => [
+                (FRAC_PI_4, WHITE),
+                (-FRAC_PI_4, 0.3),
+                (0.0, 0.55),
+                (FRAC_PI_2, 0.8),
+            ]
+            .into_iter()
+            .flat_map(|(angle, threshold)| self.hatch(angle, spacing * 2.0, |_| threshold))
+            .collect(),
+            Style::StipplePath => self.stipple_path(spacing),
+            Style::Spiral => self.spiral(spacing),
+        }
+    }
+
+    // Draws lines at an angle across the image wherever it's darker than the line's threshold
+    fn hatch(&self, angle: f32, spacing: f32, threshold: impl Fn(usize) -> f32) -> Vec<Polyline> {
+        let (size, center) = self.frame();
+        let reach = plot::distance((0.0, 0.0), size) / 2.0;
+        let along = (angle.cos(), angle.sin());
+        let across = (-along.1, along.0);
+        let lines = (2.0 * reach / spacing).ceil() as usize;
+        let steps = (2.0 * reach / STEP).ceil() as usize;
+
+        let mut polylines = vec![];
+        for i in 0..=lines {
+            let offset = -reach + i as f32 * spacing;
+            let start = (
+                center.0 + across.0 * offset - along.0 * reach,
+                center.1 + across.1 * offset - along.1 * reach,
+            );
+            let points = (0..=steps).map(|j| {
+                let t = j as f32 * STEP;
+                (start.0 + along.0 * t, start.1 + along.1 * t)
+            });
+
+            // The runs are straight, so only their ends need sending
+            let threshold = threshold(i).max(WHITE);
+            polylines.extend(
+                runs(points, |p| self.darkness_at(p) > threshold)
+                    .into_iter()
+                    .map(|run| vec![run[0], run[run.len() - 1]]),
+            );
+        }
+
+        polylines
+    }
+
+    // Scatters a dot in each cell of a grid with a chance of how dark it is there, then joins
+    // the dots up, always going to the nearest one left. Jumps longer than a few cells lift
+    // the pen, rather than drawing a line across a light patch.
+    fn stipple_path(&self, spacing: f32) -> Vec<Polyline> {
+        let (size, _) = self.frame();
+        let columns = (size.0 / spacing).ceil() as usize;
+        let rows = (size.1 / spacing).ceil() as usize;
+        let mut random = Random(0x2545_f491_4f6c_dd1d);
+        let mut cells = vec![None; columns * rows];
+        let mut left = 0;
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let dot = (
+                    self.origin.0 + (column as f32 + random.next()) * spacing,
+                    self.origin.1 + (row as f32 + random.next()) * spacing,
+                );
+                let darkness = self.darkness_at(dot);
+
+                if darkness > WHITE && random.next() < darkness {
+                    cells[row * columns + column] = Some(dot);
+                    left += 1;
+                }
+            }
+        }
+
+        let mut polylines = vec![];
+        let mut path: Polyline = vec![];
+        let mut position = self.origin;
+        while left > 0 {
+            let (cell, dot) = self.nearest_dot(&cells, columns, position, spacing);
+
+            if squared_distance(position, dot) > (4.0 * spacing).powi(2) {
+                polylines.push(std::mem::take(&mut path));
+            }
+            cells[cell] = None;
+            left -= 1;
+            position = dot;
+            path.push(dot);
+        }
+        polylines.push(path);
+
+        polylines
+            .into_iter()
+            .filter(|path| path.len() > 1)
+            .collect()
+    }
+
+    // Finds the dot left in a grid of cells that's closest to `position`, searching out a ring
+    // of cells at a time from the one it's in. There must be at least one dot left.
+    fn nearest_dot(
+        &self,
+        cells: &[Option<Point>],
+        columns: usize,
+        position: Point,
+        spacing: f32,
+    ) -> (usize, Point) {
+        let rows = (cells.len() / columns) as isize;
+        let columns = columns as isize;
+        let column = ((position.0 - self.origin.0) / spacing).floor() as isize;
+        let row = ((position.1 - self.origin.1) / spacing).floor() as isize;
+        let mut best: Option<(f32, usize, Point)> = None;
+
+        for ring in 0..=rows.max(columns) {
+            // Dots in this ring and beyond are at least `ring - 1` cells away
+            if best.is_some_and(|(d, ..)| ((ring - 1) as f32 * spacing).powi(2) > d) {
+                break;
+            }
+
+            let top_and_bottom =
+                (column - ring..=column + ring).flat_map(|c| [(row - ring, c), (row + ring, c)]);
+            let sides =
+                (row - ring + 1..row + ring).flat_map(|r| [(r, column - ring), (r, column + ring)]);
+
+            for (r, c) in top_and_bottom.chain(sides) {
+                if r < 0 || c < 0 || r >= rows || c >= columns {
+                    continue;
+                }
+
+                let cell = (r * columns + c) as usize;
+                if let Some(dot) = cells[cell] {
+                    let d = squared_distance(position, dot);
+                    if !best.is_some_and(|(closest, ..)| closest <= d) {
+                        best = Some((d, cell, dot));
+                    }
+                }
+            }
+        }
+
+        let (_, cell, dot) = best.unwrap();
+        (cell, dot)
+    }
+
+    // Follows a spiral with turns `spacing` apart, wiggling across it by up to half of that
+    fn spiral(&self, spacing: f32) -> Vec<Polyline> {
+        let (size, center) = self.frame();
+        let reach = plot::distance((0.0, 0.0), size) / 2.0;
+        // Each wiggle is about as long as the turns are apart
+        let wavelength = spacing;
+
+        let mut points = vec![];
+        let (mut angle, mut phase) = (0.0_f32, 0.0_f32);
+        loop {
+            let radius = spacing * angle / TAU;
+            if radius > reach {
+                break;
+            }
+
+            let on_path = (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            );
+            let wiggle = self.darkness_at(on_path) * spacing * 0.45 * phase.sin();
+            points.push((
+                on_path.0 + wiggle * angle.cos(),
+                on_path.1 + wiggle * angle.sin(),
+            ));
+
+            // Steps of about the same length along the spiral, however far out it is
+            let step = STEP / radius.max(spacing);
+            angle += step;
+            phase += TAU * STEP / wavelength;
+        }
+
+        runs(points.into_iter(), |p| self.darkness_at(p) > WHITE)
+    }
+
+    // The image's size and the centre of it on the bed
+    fn frame(&self) -> ((f32, f32), Point) {
+        let size = self.size();
+
+        (
+            size,
+            (self.origin.0 + size.0 / 2.0, self.origin.1 + size.1 / 2.0),
+        )
+    }
+}
+
+// Splits a line into the runs of it where `draw` holds
+fn runs(points: impl Iterator<Item = Point>, draw: impl Fn(Point) -> bool) -> Vec<Polyline> {
+    let mut runs = vec![];
+    let mut current: Polyline = vec![];
+
+    for point in points {
+        if draw(point) {
+            current.push(point);
+        } else if !current.is_empty() {
+            runs.push(std::mem::take(&mut current));
+        }
+    }
+    runs.push(current);
+
+    runs.into_iter().filter(|run| run.len() > 1).collect()
+}
+
+fn squared_distance(a: Point, b: Point) -> f32 {
+    (b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)
+}
+
+// Xorshift, so the same image always comes out the same way
+struct Random(u64);
+
+impl Random {
+    // A number from 0 up to 1
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
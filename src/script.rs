@@ -0,0 +1,49 @@
+use rquickjs::{CatchResultExt, Context, Function, Runtime};
+
+use crate::plot::Polyline;
+
+// The editor's drawing API, reimplemented without a browser
+const TOOLKIT: &str = include_str!("toolkit.js");
+
+/// Runs a Blot editor program and returns everything it passed to drawLines
+pub fn run(source: &str) -> Result<Vec<Polyline>, String> {
+    let runtime =
+        Runtime::new().map_err(|e| format!("Unable to start the JavaScript engine: {}", e))?;
+    let context = Context::full(&runtime)
+        .map_err(|e| format!("Unable to start the JavaScript engine: {}", e))?;
+
+    context.with(|ctx| {
+        let print = Function::new(
+            ctx.clone(),
+            |message: String| log::info!(target: "blot::script", "{}", message),
+        )
+        .map_err(|e| format!("Unable to set up console.log: {}", e))?;
+        ctx.globals()
+            .set("__print", print)
+            .map_err(|e| format!("Unable to set up console.log: {}", e))?;
+
+        ctx.eval::<(), _>(TOOLKIT)
+            .catch(&ctx)
+            .map_err(|e| format!("Unable to load the Blot toolkit: {}", e))?;
+        ctx.eval::<(), _>(source)
+            .catch(&ctx)
+            .map_err(|e| format!("Error in script: {}", e))?;
+
+        let lines: Vec<Vec<Vec<f64>>> =
+            ctx.globals().get("__blotLines").catch(&ctx).map_err(|e| {
+                format!("drawLines was given something other than polylines: {}", e)
+            })?;
+
+        Ok(lines
+            .into_iter()
+            .map(|polyline| {
+                polyline
+                    .into_iter()
+                    .filter(|point| point.len() >= 2)
+                    .map(|point| (point[0] as f32, point[1] as f32))
+                    .collect::<Polyline>()
+            })
+            .filter(|polyline| polyline.len() > 1)
+            .collect())
+    })
+}
@@ -0,0 +1,224 @@
+//! Simple shapes given by their measurements, traced as lines
+
+use std::f32::consts::TAU;
+
+use crate::plot::{self, Point, Polyline};
+
+/// A shape, with every length in millimetres and every angle in degrees
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Line {
+        from: Point,
+        to: Point,
+    },
+    /// An upright rectangle from its bottom left corner
+    Rect {
+        corner: Point,
+        width: f32,
+        height: f32,
+    },
+    Circle {
+        center: Point,
+        radius: f32,
+    },
+    /// An ellipse with its axes along x and y
+    Ellipse {
+        center: Point,
+        x_radius: f32,
+        y_radius: f32,
+    },
+    /// Part of a circle, starting `start` degrees counterclockwise from the positive x axis
+    /// and sweeping counterclockwise, or clockwise if `sweep` is negative
+    Arc {
+        center: Point,
+        radius: f32,
+        start: f32,
+        sweep: f32,
+    },
+    /// A closed shape through its corners in order
+    Polygon(Vec<Point>),
+    /// A quadratic Bézier curve through 3 points or a cubic one through 4, where the points
+    /// in between the ends are the control points
+    Bezier(Vec<Point>),
+}
+
+impl Shape {
+    /// Traces the shape, keeping curves within `tolerance` mm of the lines approximating them
+    pub fn trace(&self, tolerance: f32) -> Result<Polyline, String> {
+        positive("The tolerance", tolerance)?;
+
+        let mut polyline = vec![];
+
+        match *self {
+            Shape::Line { from, to } => polyline.extend([from, to]),
+            Shape::Rect {
+                corner: (x, y),
+                width,
+                height,
+            } => {
+                positive("Width", width)?;
+                positive("Height", height)?;
+
+                polyline.extend([
+                    (x, y),
+                    (x + width, y),
+                    (x + width, y + height),
+                    (x, y + height),
+                    (x, y),
+                ]);
+            }
+            Shape::Circle { center, radius } => {
+                positive("Radius", radius)?;
+
+                polyline.push((center.0 + radius, center.1));
+                plot::flatten_arc(&mut polyline, center, radius, 0.0, TAU, tolerance);
+            }
+            Shape::Ellipse {
+                center,
+                x_radius,
+                y_radius,
+            } => {
+                positive("The x radius", x_radius)?;
+                positive("The y radius", y_radius)?;
+
+                // Trace a unit circle and stretch it, with the tolerance shrunk to match so
+                // the longer axis stays within it
+                let mut circle = vec![(1.0, 0.0)];
+                let unit_tolerance = tolerance / x_radius.max(y_radius);
+                plot::flatten_arc(&mut circle, (0.0, 0.0), 1.0, 0.0, TAU, unit_tolerance);
+
+                polyline.extend(
+                    circle
+                        .into_iter()
+                        .map(|(x, y)| (center.0 + x * x_radius, center.1 + y * y_radius)),
+                );
+            }
+            Shape::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                positive("Radius", radius)?;
+                if sweep == 0.0 {
+                    return Err("The sweep can't be 0 degrees".to_string());
+                }
+
+                let (start, sweep) = (start.to_radians(), sweep.to_radians());
+                polyline.push((
+                    center.0 + radius * start.cos(),
+                    center.1 + radius * start.sin(),
+                ));
+                plot::flatten_arc(&mut polyline, center, radius, start, sweep, tolerance);
+            }
+            Shape::Polygon(ref corners) => {
+                if corners.len() < 3 {
+                    return Err(format!(
+                        "A polygon needs at least 3 corners, not {}",
+                        corners.len()
+                    ));
+                }
+
+                polyline.extend(corners);
+                polyline.push(corners[0]);
+            }
+            Shape::Bezier(ref points) => match points[..] {
+                [p0, p1, p2] => {
+                    polyline.push(p0);
+                    plot::flatten_quadratic(&mut polyline, p0, p1, p2, tolerance);
+                }
+                [p0, p1, p2, p3] => {
+                    polyline.push(p0);
+                    plot::flatten_cubic(&mut polyline, p0, p1, p2, p3, tolerance);
+                }
+                _ => {
+                    return Err(format!(
+                        "A Bézier curve needs 3 or 4 points, not {}",
+                        points.len()
+                    ))
+                }
+            },
+        }
+
+        Ok(polyline)
+    }
+}
+
+fn positive(what: &str, value: f32) -> Result<(), String> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(format!("{} must be more than 0 mm, not {}", what, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Point, b: Point) -> bool {
+        plot::distance(a, b) < 1e-3
+    }
+
+    #[test]
+    fn tolerance_must_be_positive() {
+        let circle = Shape::Circle {
+            center: (0.0, 0.0),
+            radius: 10.0,
+        };
+
+        for tolerance in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let err = circle.trace(tolerance).unwrap_err();
+            assert!(err.starts_with("The tolerance must be"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn circle_stays_within_tolerance() {
+        let circle = Shape::Circle {
+            center: (5.0, 5.0),
+            radius: 10.0,
+        };
+        let polyline = circle.trace(0.1).unwrap();
+
+        assert!(close(polyline[0], polyline[polyline.len() - 1]));
+        for pair in polyline.windows(2) {
+            let middle = ((pair[0].0 + pair[1].0) / 2.0, (pair[0].1 + pair[1].1) / 2.0);
+            assert!(10.0 - plot::distance(middle, (5.0, 5.0)) <= 0.1 + 1e-4);
+        }
+    }
+
+    #[test]
+    fn smaller_tolerance_traces_more_points() {
+        let arc = Shape::Arc {
+            center: (0.0, 0.0),
+            radius: 20.0,
+            start: 0.0,
+            sweep: 90.0,
+        };
+        let coarse = arc.trace(1.0).unwrap();
+        let fine = arc.trace(0.01).unwrap();
+
+        assert!(fine.len() > coarse.len());
+        assert!(close(fine[fine.len() - 1], (0.0, 20.0)));
+    }
+
+    #[test]
+    fn measurements_must_be_finite() {
+        let rect = Shape::Rect {
+            corner: (0.0, 0.0),
+            width: f32::INFINITY,
+            height: 5.0,
+        };
+        assert!(rect.trace(0.1).is_err());
+    }
+
+    #[test]
+    fn tiny_tolerance_is_floored() {
+        let circle = Shape::Circle {
+            center: (0.0, 0.0),
+            radius: 100.0,
+        };
+        assert!(circle.trace(1e-12).unwrap().len() <= 1001);
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::{self, Read, Write};
+use std::thread::{self, JoinHandle};
+
+use crate::comms::{BlotComms, BlotPacket, PacketState};
+use crate::framing::{FrameDecoder, Framing};
+use crate::transport::{self, PipeTransport};
+
+// Servo pulses above this are treated as the pen touching the paper
+const PEN_DOWN_THRESHOLD: u32 = 1350;
+
+/// A virtual Blot that speaks the same wire protocol as the stock firmware
+pub struct BlotSimulator {
+    incoming: FrameDecoder,
+    x: f32,
+    y: f32,
+    pen_down: bool,
+    motors_on: bool,
+}
+
+impl BlotSimulator {
+    pub fn new() -> BlotSimulator {
+        BlotSimulator {
+            // Packets from the CLI are COBS encoded and terminated by 0x00
+            incoming: FrameDecoder::new(Framing::Cobs),
+            x: 0.0,
+            y: 0.0,
+            pen_down: false,
+            motors_on: false,
+        }
+    }
+
+    /// Feeds bytes from the CLI to the simulator, returning the bytes it replies with
+    pub fn receive(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let mut replies = vec![];
+        self.incoming.push(bytes);
+
+        while let Some(packet) = self.incoming.next_packet() {
+            if let Some(reply) = self.handle(packet) {
+                replies.extend(reply);
+            }
+        }
+
+        replies
+    }
+
+    fn handle(&mut self, packet: BlotPacket) -> Option<Vec<u8>> {
+        match packet.msg.as_str() {
+            "go" => {
+                if packet.payload.len() < 8 {
+                    return None;
+                }
+                self.x = f32::from_le_bytes(packet.payload[0..4].try_into().unwrap());
+                self.y = f32::from_le_bytes(packet.payload[4..8].try_into().unwrap());
+            }
+            "servo" => {
+                if packet.payload.len() < 4 {
+                    return None;
+                }
+                let servo = u32::from_le_bytes(packet.payload[0..4].try_into().unwrap());
+                self.pen_down = servo > PEN_DOWN_THRESHOLD;
+            }
+            "motorsOn" => self.motors_on = true,
+            "motorsOff" => self.motors_on = false,
+            // The firmware zeroes its step counters, so the current position becomes (0, 0)
+            "setOrigin" | "moveTowardsOrigin" => {
+                self.x = 0.0;
+                self.y = 0.0;
+            }
+            _ => return None,
+        }
+
+        Self::ack(packet.index)
+    }
+
+    fn ack(index: Option<u8>) -> Option<Vec<u8>> {
+        let ack = BlotPacket {
+            id: uuid::Uuid::new_v4(),
+            msg: "ack".to_string(),
+            payload: vec![],
+            index,
+            state: PacketState::Queued,
+        };
+
+        let mut packed = BlotComms::pack(&ack).ok()?;
+        // 0x0a (LF) terminates each message from the Blot
+        packed.push(0x0a);
+
+        Some(packed)
+    }
+}
+
+impl Default for BlotSimulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for BlotSimulator {
+    fn drop(&mut self) {
+        log::info!(
+            "Simulated Blot stopped at ({}, {}) with pen {} and motors {}",
+            self.x,
+            self.y,
+            if self.pen_down { "down" } else { "up" },
+            if self.motors_on { "on" } else { "off" }
+        );
+    }
+}
+
+/// The CLI's end of a pipe to a simulated Blot running on its own thread
+pub struct SimTransport {
+    pipe: Option<PipeTransport>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Starts a simulated Blot and returns a transport connected to it
+pub fn spawn() -> SimTransport {
+    let (host, mut device) = transport::pipe();
+
+    let thread = thread::spawn(move || {
+        let mut sim = BlotSimulator::new();
+        let mut data = [0_u8; 512];
+
+        loop {
+            match device.read(&mut data) {
+                Ok(n) => {
+                    let replies = sim.receive(&data[..n]);
+                    if !replies.is_empty() && device.write_all(&replies).is_err() {
+                        break;
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::TimedOut => continue,
+                Err(_) => break,
+            }
+        }
+    });
+
+    SimTransport {
+        pipe: Some(host),
+        thread: Some(thread),
+    }
+}
+
+impl Drop for SimTransport {
+    fn drop(&mut self) {
+        // Closing the pipe stops the simulator, then wait for it to report its final state
+        self.pipe.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Read for SimTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.pipe {
+            Some(pipe) => pipe.read(buf),
+            None => Err(io::Error::from(io::ErrorKind::BrokenPipe)),
+        }
+    }
+}
+
+impl Write for SimTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.pipe {
+            Some(pipe) => pipe.write(buf),
+            None => Err(io::Error::from(io::ErrorKind::BrokenPipe)),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,442 @@
+use std::str::FromStr;
+
+use roxmltree::{Document, Node};
+use svgtypes::{
+    Length, LengthUnit, PointsParser, SimplePathSegment, SimplifyingPathParser, ViewBox,
+};
+
+use crate::plot::{self, Affine, Point, Polyline};
+
+// SVG user units without a physical size are CSS pixels, at 96 per inch
+const MM_PER_PX: f32 = 25.4 / 96.0;
+
+// Control point distance for approximating a quarter ellipse with a cubic Bézier curve
+const KAPPA: f32 = 0.552_284_8;
+
+// Elements whose children are never rendered directly
+const SKIPPED_ELEMENTS: [&str; 11] = [
+    "defs", "clipPath", "mask", "symbol", "marker", "pattern", "style", "script", "title", "desc",
+    "metadata",
+];
+
+/// Parses an SVG document into polylines in millimetres, with the y axis pointing up
+pub fn parse(source: &str, tolerance: f32) -> Result<Vec<Polyline>, String> {
+    let doc = Document::parse(source).map_err(|e| format!("Invalid SVG: {}", e))?;
+    let root = doc.root_element();
+
+    if root.tag_name().name() != "svg" {
+        return Err("Invalid SVG: root element is not <svg>".to_string());
+    }
+
+    let view_box = root
+        .attribute("viewBox")
+        .and_then(|v| ViewBox::from_str(v).ok());
+    let width = root.attribute("width").and_then(length_mm);
+    let height = root.attribute("height").and_then(length_mm);
+
+    let (min_x, min_y, scale_x, scale_y, height_mm) = match view_box {
+        Some(vb) => {
+            let (vb_w, vb_h) = (vb.w as f32, vb.h as f32);
+            let width_mm = width.unwrap_or(vb_w * MM_PER_PX);
+            let height_mm = height.unwrap_or(vb_h * MM_PER_PX);
+
+            (
+                vb.x as f32,
+                vb.y as f32,
+                width_mm / vb_w,
+                height_mm / vb_h,
+                Some(height_mm),
+            )
+        }
+        None => (0.0, 0.0, MM_PER_PX, MM_PER_PX, height),
+    };
+
+    // Map user units onto millimetres
+    let document = Affine::new(
+        scale_x,
+        0.0,
+        0.0,
+        scale_y,
+        -min_x * scale_x,
+        -min_y * scale_y,
+    );
+
+    let mut polylines = vec![];
+    walk(root, &document, tolerance, &mut polylines)?;
+    let polylines: Vec<Polyline> = polylines.into_iter().filter(|p| p.len() > 1).collect();
+
+    // Flip the y axis so the drawing isn't mirrored. Without a height, the document is taken
+    // to end at the bottom of what's drawn in it.
+    let height_mm = height_mm.unwrap_or_else(|| {
+        polylines
+            .iter()
+            .flatten()
+            .map(|&(_, y)| y)
+            .fold(0.0, f32::max)
+    });
+
+    Ok(polylines
+        .into_iter()
+        .map(|p| p.into_iter().map(|(x, y)| (x, height_mm - y)).collect())
+        .collect())
+}
+
+fn walk(
+    node: Node,
+    parent: &Affine,
+    tolerance: f32,
+    out: &mut Vec<Polyline>,
+) -> Result<(), String> {
+    if node.attribute("display") == Some("none") {
+        return Ok(());
+    }
+
+    let transform = match node.attribute("transform") {
+        Some(t) => {
+            let t = svgtypes::Transform::from_str(t)
+                .map_err(|e| format!("Invalid transform \"{}\": {}", t, e))?;
+            Affine::new(
+                t.a as f32, t.b as f32, t.c as f32, t.d as f32, t.e as f32, t.f as f32,
+            )
+            .then(parent)
+        }
+        None => *parent,
+    };
+
+    match node.tag_name().name() {
+        "path" => {
+            if let Some(d) = node.attribute("d") {
+                path(d, &transform, tolerance, out)?;
+            }
+        }
+        "line" => out.push(vec![
+            transform.apply((number(node, "x1"), number(node, "y1"))),
+            transform.apply((number(node, "x2"), number(node, "y2"))),
+        ]),
+        "polyline" | "polygon" => {
+            let mut polyline: Polyline = PointsParser::from(node.attribute("points").unwrap_or(""))
+                .map(|(x, y)| transform.apply((x as f32, y as f32)))
+                .collect();
+
+            if node.tag_name().name() == "polygon" && !polyline.is_empty() {
+                polyline.push(polyline[0]);
+            }
+
+            out.push(polyline);
+        }
+        "rect" => out.extend(rect(node, &transform, tolerance)),
+        "circle" => {
+            let r = number(node, "r");
+            out.push(ellipse(
+                (number(node, "cx"), number(node, "cy")),
+                r,
+                r,
+                &transform,
+                tolerance,
+            ));
+        }
+        "ellipse" => out.push(ellipse(
+            (number(node, "cx"), number(node, "cy")),
+            number(node, "rx"),
+            number(node, "ry"),
+            &transform,
+            tolerance,
+        )),
+        name if SKIPPED_ELEMENTS.contains(&name) => {}
+        _ => {
+            for child in node.children().filter(|n| n.is_element()) {
+                walk(child, &transform, tolerance, out)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn path(
+    d: &str,
+    transform: &Affine,
+    tolerance: f32,
+    out: &mut Vec<Polyline>,
+) -> Result<(), String> {
+    let mut current: Polyline = vec![];
+    let mut start: Point = (0.0, 0.0);
+    let mut last: Point = (0.0, 0.0);
+
+    for segment in SimplifyingPathParser::from(d) {
+        let segment = segment.map_err(|e| format!("Invalid path data: {}", e))?;
+
+        match segment {
+            SimplePathSegment::MoveTo { x, y } => {
+                out.push(std::mem::take(&mut current));
+                start = transform.apply((x as f32, y as f32));
+                last = start;
+                current.push(start);
+            }
+            SimplePathSegment::LineTo { x, y } => {
+                last = transform.apply((x as f32, y as f32));
+                current.push(last);
+            }
+            SimplePathSegment::Quadratic { x1, y1, x, y } => {
+                let p1 = transform.apply((x1 as f32, y1 as f32));
+                let p2 = transform.apply((x as f32, y as f32));
+                plot::flatten_quadratic(&mut current, last, p1, p2, tolerance);
+                last = p2;
+            }
+            SimplePathSegment::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let p1 = transform.apply((x1 as f32, y1 as f32));
+                let p2 = transform.apply((x2 as f32, y2 as f32));
+                let p3 = transform.apply((x as f32, y as f32));
+                plot::flatten_cubic(&mut current, last, p1, p2, p3, tolerance);
+                last = p3;
+            }
+            SimplePathSegment::ClosePath => {
+                current.push(start);
+                last = start;
+                out.push(std::mem::take(&mut current));
+            }
+        }
+    }
+
+    out.push(current);
+
+    Ok(())
+}
+
+// Rects without a positive width and height aren't rendered
+fn rect(node: Node, transform: &Affine, tolerance: f32) -> Option<Polyline> {
+    let (x, y) = (number(node, "x"), number(node, "y"));
+    let (w, h) = (number(node, "width"), number(node, "height"));
+
+    if w <= 0.0 || h <= 0.0 {
+        return None;
+    }
+
+    // A missing rx or ry takes the value of the other one
+    let (rx, ry) = match (node.attribute("rx"), node.attribute("ry")) {
+        (Some(_), None) => (number(node, "rx"), number(node, "rx")),
+        (None, Some(_)) => (number(node, "ry"), number(node, "ry")),
+        _ => (number(node, "rx"), number(node, "ry")),
+    };
+    let rx = rx.clamp(0.0, w / 2.0);
+    let ry = ry.clamp(0.0, h / 2.0);
+
+    if rx == 0.0 || ry == 0.0 {
+        return Some(
+            [(x, y), (x + w, y), (x + w, y + h), (x, y + h), (x, y)]
+                .into_iter()
+                .map(|p| transform.apply(p))
+                .collect(),
+        );
+    }
+
+    let mut polyline = vec![transform.apply((x + rx, y))];
+    let corners = [
+        ((x + w - rx, y), (x + w, y + ry), (1.0, 0.0), (0.0, -1.0)),
+        (
+            (x + w, y + h - ry),
+            (x + w - rx, y + h),
+            (0.0, 1.0),
+            (1.0, 0.0),
+        ),
+        ((x + rx, y + h), (x, y + h - ry), (-1.0, 0.0), (0.0, 1.0)),
+        ((x, y + ry), (x + rx, y), (0.0, -1.0), (-1.0, 0.0)),
+    ];
+
+    for (edge_end, corner_end, out_dir, in_dir) in corners {
+        let p0 = transform.apply(edge_end);
+        let p1 = transform.apply((
+            edge_end.0 + out_dir.0 * rx * KAPPA,
+            edge_end.1 + out_dir.1 * ry * KAPPA,
+        ));
+        let p2 = transform.apply((
+            corner_end.0 + in_dir.0 * rx * KAPPA,
+            corner_end.1 + in_dir.1 * ry * KAPPA,
+        ));
+        let p3 = transform.apply(corner_end);
+
+        polyline.push(p0);
+        plot::flatten_cubic(&mut polyline, p0, p1, p2, p3, tolerance);
+    }
+
+    Some(polyline)
+}
+
+fn ellipse(center: Point, rx: f32, ry: f32, transform: &Affine, tolerance: f32) -> Polyline {
+    let (cx, cy) = center;
+    let quadrants = [
+        (
+            (cx + rx, cy),
+            (cx + rx, cy + ry * KAPPA),
+            (cx + rx * KAPPA, cy + ry),
+            (cx, cy + ry),
+        ),
+        (
+            (cx, cy + ry),
+            (cx - rx * KAPPA, cy + ry),
+            (cx - rx, cy + ry * KAPPA),
+            (cx - rx, cy),
+        ),
+        (
+            (cx - rx, cy),
+            (cx - rx, cy - ry * KAPPA),
+            (cx - rx * KAPPA, cy - ry),
+            (cx, cy - ry),
+        ),
+        (
+            (cx, cy - ry),
+            (cx + rx * KAPPA, cy - ry),
+            (cx + rx, cy - ry * KAPPA),
+            (cx + rx, cy),
+        ),
+    ];
+
+    let mut polyline = vec![transform.apply((cx + rx, cy))];
+    for (p0, p1, p2, p3) in quadrants {
+        plot::flatten_cubic(
+            &mut polyline,
+            transform.apply(p0),
+            transform.apply(p1),
+            transform.apply(p2),
+            transform.apply(p3),
+            tolerance,
+        );
+    }
+
+    polyline
+}
+
+// Reads a numeric attribute in user units, defaulting to 0
+fn number(node: Node, name: &str) -> f32 {
+    node.attribute(name)
+        .and_then(|v| Length::from_str(v).ok())
+        .map(|l| l.number as f32)
+        .unwrap_or(0.0)
+}
+
+// Converts an absolute length such as "100mm" to millimetres
+fn length_mm(value: &str) -> Option<f32> {
+    let length = Length::from_str(value).ok()?;
+    let number = length.number as f32;
+
+    match length.unit {
+        LengthUnit::None | LengthUnit::Px => Some(number * MM_PER_PX),
+        LengthUnit::Mm => Some(number),
+        LengthUnit::Cm => Some(number * 10.0),
+        LengthUnit::In => Some(number * 25.4),
+        LengthUnit::Pt => Some(number * 25.4 / 72.0),
+        LengthUnit::Pc => Some(number * 25.4 / 6.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Point, b: Point) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    #[test]
+    fn view_box_maps_onto_physical_size_with_y_up() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100mm" height="50mm"
+            viewBox="10 10 200 100"><path d="M 10 10 L 210 110"/></svg>"#;
+
+        let polylines = parse(svg, 0.1).unwrap();
+
+        assert_eq!(polylines.len(), 1);
+        assert!(close(polylines[0][0], (0.0, 50.0)));
+        assert!(close(polylines[0][1], (100.0, 0.0)));
+    }
+
+    #[test]
+    fn rects_without_positive_size_are_skipped() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+            <rect x="10" y="10" width="-5" height="20"/>
+            <rect x="10" y="10" width="20" height="0"/>
+            <rect x="10" y="10" width="20" height="20" rx="-3"/>
+        </svg>"#;
+
+        let polylines = parse(svg, 0.1).unwrap();
+
+        // Only the last rect is drawn, with a negative rx treated as square corners
+        assert_eq!(polylines.len(), 1);
+        assert_eq!(polylines[0].len(), 5);
+    }
+
+    #[test]
+    fn rounded_rect_stays_inside_its_bounds() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100mm" height="100mm"
+            viewBox="0 0 100 100"><rect x="10" y="10" width="40" height="20" rx="50"/></svg>"#;
+
+        let polylines = parse(svg, 0.05).unwrap();
+
+        // rx is limited to half the width and ry, which takes rx, to half the height
+        assert_eq!(polylines.len(), 1);
+        assert!(polylines[0].len() > 5);
+        for &(x, y) in &polylines[0] {
+            assert!((10.0 - 1e-3..=50.0 + 1e-3).contains(&x));
+            assert!((70.0 - 1e-3..=90.0 + 1e-3).contains(&y));
+        }
+    }
+
+    #[test]
+    fn svg_without_height_flips_about_its_content() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg">
+            <line x1="0" y1="0" x2="0" y2="96"/>
+        </svg>"#;
+
+        let polylines = parse(svg, 0.1).unwrap();
+
+        // 96 px is an inch, and the top of the line ends up at the top of the drawing
+        assert!(close(polylines[0][0], (0.0, 25.4)));
+        assert!(close(polylines[0][1], (0.0, 0.0)));
+    }
+
+    #[test]
+    fn transforms_nest_and_hidden_elements_are_skipped() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100mm" height="100mm"
+            viewBox="0 0 100 100">
+            <g transform="translate(10 0)">
+                <line x1="0" y1="0" x2="0" y2="10" transform="scale(2)"/>
+                <line x1="0" y1="0" x2="5" y2="5" display="none"/>
+            </g>
+            <defs><line x1="0" y1="0" x2="5" y2="5"/></defs>
+        </svg>"#;
+
+        let polylines = parse(svg, 0.1).unwrap();
+
+        assert_eq!(polylines.len(), 1);
+        assert!(close(polylines[0][0], (10.0, 100.0)));
+        assert!(close(polylines[0][1], (10.0, 80.0)));
+    }
+
+    #[test]
+    fn closed_path_returns_to_its_start() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="10mm" height="10mm"
+            viewBox="0 0 10 10"><path d="M 1 1 h 5 v 5 z M 8 8 L 9 9"/></svg>"#;
+
+        let polylines = parse(svg, 0.1).unwrap();
+
+        assert_eq!(polylines.len(), 2);
+        assert_eq!(polylines[0].len(), 4);
+        assert_eq!(polylines[0].first(), polylines[0].last());
+    }
+
+    #[test]
+    fn lengths_convert_to_mm() {
+        assert_eq!(length_mm("10mm"), Some(10.0));
+        assert_eq!(length_mm("2cm"), Some(20.0));
+        assert_eq!(length_mm("1in"), Some(25.4));
+        assert!(close((length_mm("96").unwrap(), 0.0), (25.4, 0.0)));
+        assert_eq!(length_mm("50%"), None);
+    }
+}
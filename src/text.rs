@@ -0,0 +1,192 @@
+use std::str::FromStr;
+
+use crate::font::Font;
+use crate::plot::{Affine, Point, Polyline};
+
+pub use crate::font::FONT_NAMES;
+
+/// Which part of each line sits on the anchor point
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Align {
+    /// Lines start at the anchor
+    #[default]
+    Left,
+    /// Lines are centred on the anchor
+    Center,
+    /// Lines end at the anchor
+    Right,
+}
+
+impl FromStr for Align {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(Align::Left),
+            "center" | "centre" => Ok(Align::Center),
+            "right" => Ok(Align::Right),
+            _ => Err(format!("Alignment can be left, center or right, not {}", s)),
+        }
+    }
+}
+
+/// How to lay out text
+pub struct TextOptions {
+    /// Name of a built-in font
+    pub font: String,
+    /// Height of capital letters, in millimetres
+    pub size: f32,
+    /// Where the baseline of the first line is anchored
+    pub at: Point,
+    /// Width in millimetres to wrap lines at between words, if any
+    pub wrap: Option<f32>,
+    pub align: Align,
+    /// Degrees to rotate the text counterclockwise around the anchor
+    pub rotate: f32,
+    /// Distance between baselines, as a multiple of `size`
+    pub line_spacing: f32,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions {
+            font: FONT_NAMES[0].to_string(),
+            size: 5.0,
+            at: (0.0, 0.0),
+            wrap: None,
+            align: Align::Left,
+            rotate: 0.0,
+            line_spacing: 1.5,
+        }
+    }
+}
+
+/// Lays out text in a single-stroke font, returning the strokes to draw it
+///
+/// Lines break at newlines, and between words once they get wider than `wrap`. Each line
+/// below the first is `line_spacing` times the size further down.
+pub fn layout(text: &str, options: &TextOptions) -> Result<Vec<Polyline>, String> {
+    if !(options.size.is_finite() && options.size > 0.0) {
+        return Err(format!(
+            "Text size must be more than 0 mm, not {}",
+            options.size
+        ));
+    }
+    if !options.rotate.is_finite() {
+        return Err(format!(
+            "The rotation must be a number of degrees, not {}",
+            options.rotate
+        ));
+    }
+
+    let font = Font::named(&options.font).ok_or_else(|| {
+        format!(
+            "There's no font called {}. Fonts available: {}",
+            options.font,
+            FONT_NAMES.join(", ")
+        )
+    })?;
+
+    let lines: Vec<String> = text
+        .lines()
+        .flat_map(|line| wrap(&font, line, options.size, options.wrap))
+        .collect();
+
+    let (sin, cos) = options.rotate.to_radians().sin_cos();
+    let placement = Affine::new(cos, sin, -sin, cos, options.at.0, options.at.1);
+    let mut polylines = vec![];
+
+    for (i, line) in lines.iter().enumerate() {
+        let (strokes, width) = font.line(line, options.size);
+        let offset = match options.align {
+            Align::Left => 0.0,
+            Align::Center => -width / 2.0,
+            Align::Right => -width,
+        };
+        let baseline = -(i as f32) * options.size * options.line_spacing;
+
+        polylines.extend(strokes.into_iter().map(|stroke| {
+            stroke
+                .into_iter()
+                .map(|(x, y)| placement.apply((x + offset, y + baseline)))
+                .collect()
+        }));
+    }
+
+    Ok(polylines)
+}
+
+// Breaks a line between words so that each piece fits within `width`, where it can. Words
+// too long to fit get a line to themselves.
+fn wrap(font: &Font, line: &str, size: f32, width: Option<f32>) -> Vec<String> {
+    let Some(width) = width else {
+        return vec![line.to_string()];
+    };
+
+    let mut lines = vec![];
+    let mut current = String::new();
+
+    for word in line.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+
+        if !current.is_empty() && font.line(&candidate, size).1 > width {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        } else {
+            current = candidate;
+        }
+    }
+    lines.push(current);
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_must_be_positive() {
+        for size in [0.0, -5.0, f32::NAN, f32::INFINITY] {
+            let options = TextOptions {
+                size,
+                ..TextOptions::default()
+            };
+            assert!(layout("Hi", &options).is_err(), "size {}", size);
+        }
+    }
+
+    #[test]
+    fn every_font_lays_out_text() {
+        for font in FONT_NAMES {
+            let options = TextOptions {
+                font: font.to_string(),
+                size: 10.0,
+                ..TextOptions::default()
+            };
+            let strokes = layout("Hello, Blot!", &options).unwrap();
+            assert!(!strokes.is_empty(), "{}", font);
+
+            // Capitals are `size` tall
+            let top = strokes
+                .iter()
+                .flatten()
+                .map(|p| p.1)
+                .fold(f32::MIN, f32::max);
+            assert!((top - 10.0).abs() < 1.5, "{} reaches {}", font, top);
+        }
+    }
+
+    #[test]
+    fn unknown_font_lists_the_others() {
+        let options = TextOptions {
+            font: "gothic".to_string(),
+            ..TextOptions::default()
+        };
+        let err = layout("Hi", &options).unwrap_err();
+        assert!(err.contains("futural, timesr, scripts"), "{}", err);
+    }
+}
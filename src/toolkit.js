@@ -0,0 +1,597 @@
+// A headless version of the Blot editor's drawing API: drawLines, setDocDimensions, Turtle and bt.
+// Everything passed to drawLines is collected in globalThis.__blotLines for the CLI to plot.
+
+globalThis.__blotLines = [];
+globalThis.__blotDocDimensions = [125, 125];
+
+const console = {
+  log: (...args) => __print(args.map(__format).join(" ")),
+  warn: (...args) => __print(args.map(__format).join(" ")),
+  error: (...args) => __print(args.map(__format).join(" ")),
+};
+
+function __format(value) {
+  if (typeof value === "string") return value;
+  try {
+    return JSON.stringify(value);
+  } catch {
+    return String(value);
+  }
+}
+
+function setDocDimensions(width, height) {
+  globalThis.__blotDocDimensions = [width, height];
+}
+
+function drawLines(polylines, style = {}) {
+  for (const polyline of polylines) {
+    globalThis.__blotLines.push(polyline.map(([x, y]) => [x, y]));
+  }
+}
+
+class Turtle {
+  constructor() {
+    this.drawing = true;
+    this.position = [0, 0];
+    this.angle = 0;
+    this.path = [[[0, 0]]];
+  }
+
+  get pos() {
+    return this.position;
+  }
+
+  set pos(point) {
+    this.position = point;
+  }
+
+  up() {
+    this.drawing = false;
+    return this;
+  }
+
+  down() {
+    this.drawing = true;
+    return this;
+  }
+
+  goTo([x, y]) {
+    if (this.drawing) {
+      this.path.at(-1).push([x, y]);
+    } else {
+      this.jump([x, y]);
+      this.drawing = false;
+    }
+
+    this.position = [x, y];
+    return this;
+  }
+
+  jump([x, y]) {
+    const lastPath = this.path.at(-1);
+    if (lastPath.length === 1) {
+      lastPath[0] = [x, y];
+    } else {
+      this.path.push([[x, y]]);
+    }
+
+    this.position = [x, y];
+    return this;
+  }
+
+  step([dx, dy]) {
+    return this.goTo([this.position[0] + dx, this.position[1] + dy]);
+  }
+
+  forward(distance) {
+    const angle = (this.angle / 180) * Math.PI;
+    return this.goTo([
+      this.position[0] + distance * Math.cos(angle),
+      this.position[1] + distance * Math.sin(angle),
+    ]);
+  }
+
+  arc(angle, radius) {
+    if (angle === 0 || radius === 0) return this;
+
+    // The arc's center is to the turtle's left when turning left, and to its right otherwise
+    const heading = (this.angle / 180) * Math.PI;
+    const side = angle > 0 ? 1 : -1;
+    const center = [
+      this.position[0] - side * radius * Math.sin(heading),
+      this.position[1] + side * radius * Math.cos(heading),
+    ];
+    const startAngle = Math.atan2(this.position[1] - center[1], this.position[0] - center[0]);
+    const steps = Math.max(1, Math.ceil(Math.abs(angle)));
+
+    for (let i = 1; i <= steps; i++) {
+      const a = startAngle + ((angle / 180) * Math.PI * i) / steps;
+      this.goTo([center[0] + Math.abs(radius) * Math.cos(a), center[1] + Math.abs(radius) * Math.sin(a)]);
+    }
+
+    this.angle += angle;
+    return this;
+  }
+
+  setAngle(theta) {
+    this.angle = theta;
+    return this;
+  }
+
+  right(theta) {
+    this.angle -= theta;
+    return this;
+  }
+
+  left(theta) {
+    this.angle += theta;
+    return this;
+  }
+
+  lines() {
+    return bt.copy(this.path).filter((polyline) => polyline.length > 1);
+  }
+
+  copy() {
+    const t = new Turtle();
+    t.drawing = this.drawing;
+    t.position = [...this.position];
+    t.angle = this.angle;
+    t.path = bt.copy(this.path);
+    return t;
+  }
+
+  join(turtle) {
+    this.path.push(...bt.copy(turtle.path));
+    this.position = [...turtle.position];
+    this.angle = turtle.angle;
+    return this;
+  }
+
+  apply(fn) {
+    fn(this);
+    return this;
+  }
+
+  iteratePath(fn) {
+    bt.iteratePoints(this.path, fn);
+    return this;
+  }
+
+  get start() {
+    return this.path[0][0];
+  }
+
+  get end() {
+    return this.path.at(-1).at(-1);
+  }
+}
+
+const bt = (() => {
+  let seed = Math.floor(Math.random() * 2 ** 32);
+
+  // mulberry32, so that setRandSeed gives repeatable drawings
+  function rand() {
+    seed = (seed + 0x6d2b79f5) | 0;
+    let t = seed;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  }
+
+  function setRandSeed(newSeed) {
+    seed = newSeed | 0;
+    perlin = null;
+  }
+
+  function randInRange(min, max) {
+    return rand() * (max - min) + min;
+  }
+
+  function randIntInRange(min, max) {
+    return Math.floor(rand() * (Math.floor(max) - Math.ceil(min) + 1)) + Math.ceil(min);
+  }
+
+  // Perlin noise in the style of p5.js, which the editor's noise is based on
+  const PERLIN_SIZE = 4095;
+  let perlin = null;
+
+  function noise(point, { octaves = 4, falloff = 50 } = {}) {
+    let [x, y = 0, z = 0] = typeof point === "number" ? [point] : point;
+    if (perlin === null) {
+      perlin = Array.from({ length: PERLIN_SIZE + 1 }, () => rand());
+    }
+
+    const scaledCosine = (i) => 0.5 * (1.0 - Math.cos(i * Math.PI));
+    x = Math.abs(x);
+    y = Math.abs(y);
+    z = Math.abs(z);
+
+    let xi = Math.floor(x);
+    let yi = Math.floor(y);
+    let zi = Math.floor(z);
+    let xf = x - xi;
+    let yf = y - yi;
+    let zf = z - zi;
+
+    let result = 0;
+    let amplitude = 0.5;
+
+    for (let o = 0; o < octaves; o++) {
+      let of = xi + (yi << 4) + (zi << 8);
+      const rxf = scaledCosine(xf);
+      const ryf = scaledCosine(yf);
+
+      let n1 = perlin[of & PERLIN_SIZE];
+      n1 += rxf * (perlin[(of + 1) & PERLIN_SIZE] - n1);
+      let n2 = perlin[(of + 16) & PERLIN_SIZE];
+      n2 += rxf * (perlin[(of + 17) & PERLIN_SIZE] - n2);
+      n1 += ryf * (n2 - n1);
+
+      of += 256;
+      n2 = perlin[of & PERLIN_SIZE];
+      n2 += rxf * (perlin[(of + 1) & PERLIN_SIZE] - n2);
+      let n3 = perlin[(of + 16) & PERLIN_SIZE];
+      n3 += rxf * (perlin[(of + 17) & PERLIN_SIZE] - n3);
+      n2 += ryf * (n3 - n2);
+
+      n1 += scaledCosine(zf) * (n2 - n1);
+      result += n1 * amplitude;
+      amplitude *= falloff / 100;
+
+      xi <<= 1;
+      xf *= 2;
+      yi <<= 1;
+      yf *= 2;
+      zi <<= 1;
+      zf *= 2;
+
+      if (xf >= 1.0) {
+        xi++;
+        xf--;
+      }
+      if (yf >= 1.0) {
+        yi++;
+        yf--;
+      }
+      if (zf >= 1.0) {
+        zi++;
+        zf--;
+      }
+    }
+
+    return result;
+  }
+
+  function copy(obj) {
+    return JSON.parse(JSON.stringify(obj));
+  }
+
+  function bounds(polylines) {
+    let xMin = Infinity;
+    let xMax = -Infinity;
+    let yMin = Infinity;
+    let yMax = -Infinity;
+
+    for (const polyline of polylines) {
+      for (const [x, y] of polyline) {
+        xMin = Math.min(xMin, x);
+        xMax = Math.max(xMax, x);
+        yMin = Math.min(yMin, y);
+        yMax = Math.max(yMax, y);
+      }
+    }
+
+    const xCenter = (xMin + xMax) / 2;
+    const yCenter = (yMin + yMax) / 2;
+
+    return {
+      xMin,
+      xMax,
+      yMin,
+      yMax,
+      width: xMax - xMin,
+      height: yMax - yMin,
+      lt: [xMin, yMax],
+      ct: [xCenter, yMax],
+      rt: [xMax, yMax],
+      lc: [xMin, yCenter],
+      cc: [xCenter, yCenter],
+      rc: [xMax, yCenter],
+      lb: [xMin, yMin],
+      cb: [xCenter, yMin],
+      rb: [xMax, yMin],
+    };
+  }
+
+  function transformPoints(polylines, fn) {
+    for (const polyline of polylines) {
+      for (let i = 0; i < polyline.length; i++) {
+        polyline[i] = fn(polyline[i]);
+      }
+    }
+    return polylines;
+  }
+
+  function translate(polylines, to, origin = [0, 0]) {
+    const [dx, dy] = [to[0] - origin[0], to[1] - origin[1]];
+    return transformPoints(polylines, ([x, y]) => [x + dx, y + dy]);
+  }
+
+  function rotate(polylines, angle, origin = bounds(polylines).cc) {
+    const radians = (angle / 180) * Math.PI;
+    const [cos, sin] = [Math.cos(radians), Math.sin(radians)];
+    const [ox, oy] = origin;
+
+    return transformPoints(polylines, ([x, y]) => [
+      ox + (x - ox) * cos - (y - oy) * sin,
+      oy + (x - ox) * sin + (y - oy) * cos,
+    ]);
+  }
+
+  function scale(polylines, factor, origin = bounds(polylines).cc) {
+    const [sx, sy] = typeof factor === "number" ? [factor, factor] : factor;
+    const [ox, oy] = origin;
+
+    return transformPoints(polylines, ([x, y]) => [ox + (x - ox) * sx, oy + (y - oy) * sy]);
+  }
+
+  function originate(polylines) {
+    return translate(polylines, [0, 0], bounds(polylines).cc);
+  }
+
+  function join(polylines0, ...rest) {
+    for (const polylines of rest) {
+      polylines0.push(...polylines);
+    }
+    return polylines0;
+  }
+
+  // Distance along each point of a polyline
+  function lengths(polyline) {
+    const result = [0];
+    for (let i = 1; i < polyline.length; i++) {
+      const [x0, y0] = polyline[i - 1];
+      const [x1, y1] = polyline[i];
+      result.push(result[i - 1] + Math.hypot(x1 - x0, y1 - y0));
+    }
+    return result;
+  }
+
+  function iteratePoints(polylines, fn) {
+    const toRemove = [];
+    const toBreak = [];
+
+    polylines.forEach((polyline, i) => {
+      const distances = lengths(polyline);
+      const total = distances.at(-1) || 1;
+
+      polyline.forEach((point, j) => {
+        const result = fn(point, distances[j] / total);
+        if (result === "REMOVE") {
+          toRemove.push([i, j]);
+        } else if (result === "BREAK") {
+          toBreak.push([i, j]);
+        } else if (Array.isArray(result)) {
+          polyline[j] = result;
+        }
+      });
+    });
+
+    for (const [i, j] of toRemove.reverse()) {
+      polylines[i].splice(j, 1);
+    }
+
+    for (const [i, j] of toBreak.reverse()) {
+      const tail = polylines[i].splice(j + 1);
+      if (tail.length > 0) {
+        polylines.splice(i + 1, 0, [polylines[i][j], ...tail]);
+      }
+    }
+
+    return polylines;
+  }
+
+  function pointAt(polyline, t) {
+    const distances = lengths(polyline);
+    const target = distances.at(-1) * Math.min(Math.max(t, 0), 1);
+    const i = Math.max(1, distances.findIndex((d) => d >= target));
+    const [x0, y0] = polyline[i - 1];
+    const [x1, y1] = polyline[i];
+    const span = distances[i] - distances[i - 1] || 1;
+    const u = (target - distances[i - 1]) / span;
+
+    return { point: [x0 + (x1 - x0) * u, y0 + (y1 - y0) * u], index: i, angle: Math.atan2(y1 - y0, x1 - x0) };
+  }
+
+  function flatten(polylines) {
+    return polylines.length === 1 ? polylines[0] : polylines.flat();
+  }
+
+  function getPoint(polylines, t) {
+    return pointAt(flatten(polylines), t).point;
+  }
+
+  function getAngle(polylines, t) {
+    return (pointAt(flatten(polylines), t).angle / Math.PI) * 180;
+  }
+
+  function getNormal(polylines, t) {
+    const angle = pointAt(flatten(polylines), t).angle;
+    return [-Math.sin(angle), Math.cos(angle)];
+  }
+
+  function trim(polylines, tStart, tEnd) {
+    for (let i = 0; i < polylines.length; i++) {
+      const polyline = polylines[i];
+      const start = pointAt(polyline, tStart);
+      const end = pointAt(polyline, tEnd);
+      polylines[i] = [start.point, ...polyline.slice(start.index, end.index), end.point];
+    }
+    return polylines;
+  }
+
+  function resample(polylines, spacing) {
+    for (let i = 0; i < polylines.length; i++) {
+      const polyline = polylines[i];
+      const total = lengths(polyline).at(-1);
+      const steps = Math.max(1, Math.round(total / spacing));
+      const resampled = [];
+      for (let s = 0; s <= steps; s++) {
+        resampled.push(pointAt(polyline, s / steps).point);
+      }
+      polylines[i] = resampled;
+    }
+    return polylines;
+  }
+
+  // Ramer–Douglas–Peucker simplification
+  function simplifyPolyline(points, tolerance) {
+    if (points.length < 3) return points;
+
+    const [x0, y0] = points[0];
+    const [x1, y1] = points.at(-1);
+    const length = Math.hypot(x1 - x0, y1 - y0);
+    let maxDistance = 0;
+    let index = 0;
+
+    for (let i = 1; i < points.length - 1; i++) {
+      const [x, y] = points[i];
+      const distance =
+        length === 0
+          ? Math.hypot(x - x0, y - y0)
+          : Math.abs((y1 - y0) * x - (x1 - x0) * y + x1 * y0 - y1 * x0) / length;
+      if (distance > maxDistance) {
+        maxDistance = distance;
+        index = i;
+      }
+    }
+
+    if (maxDistance <= tolerance) return [points[0], points.at(-1)];
+
+    return [
+      ...simplifyPolyline(points.slice(0, index + 1), tolerance).slice(0, -1),
+      ...simplifyPolyline(points.slice(index), tolerance),
+    ];
+  }
+
+  function simplify(polylines, tolerance) {
+    for (let i = 0; i < polylines.length; i++) {
+      polylines[i] = simplifyPolyline(polylines[i], tolerance);
+    }
+    return polylines;
+  }
+
+  function catmullRom(points, steps = 1000) {
+    const extended = [points[0], ...points, points.at(-1)];
+    const result = [];
+    const perSegment = Math.max(1, Math.floor(steps / (points.length - 1)));
+
+    for (let i = 1; i < extended.length - 2; i++) {
+      const [p0, p1, p2, p3] = extended.slice(i - 1, i + 3);
+      for (let s = 0; s < perSegment; s++) {
+        const t = s / perSegment;
+        const t2 = t * t;
+        const t3 = t2 * t;
+        result.push(
+          [0, 1].map(
+            (k) =>
+              0.5 *
+              (2 * p1[k] +
+                (-p0[k] + p2[k]) * t +
+                (2 * p0[k] - 5 * p1[k] + 4 * p2[k] - p3[k]) * t2 +
+                (-p0[k] + 3 * p1[k] - 3 * p2[k] + p3[k]) * t3),
+          ),
+        );
+      }
+    }
+
+    result.push(points.at(-1));
+    return result;
+  }
+
+  // Clamped uniform B-spline through the control points, evaluated with de Boor's algorithm
+  function nurbs(points, { steps = 100, degree = 2 } = {}) {
+    const n = points.length - 1;
+    const p = Math.min(degree, n);
+    const knots = [];
+    for (let i = 0; i <= n + p + 1; i++) {
+      knots.push(i <= p ? 0 : i > n ? 1 : (i - p) / (n - p + 1));
+    }
+
+    const result = [];
+    for (let s = 0; s <= steps; s++) {
+      const t = Math.min(s / steps, 1 - 1e-9);
+      let k = p;
+      while (k < n && knots[k + 1] <= t) k++;
+
+      const d = [];
+      for (let j = 0; j <= p; j++) d.push([...points[j + k - p]]);
+      for (let r = 1; r <= p; r++) {
+        for (let j = p; j >= r; j--) {
+          const i = j + k - p;
+          const alpha = (t - knots[i]) / (knots[i + 1 + p - r] - knots[i] || 1);
+          d[j] = [0, 1].map((c) => (1 - alpha) * d[j - 1][c] + alpha * d[j][c]);
+        }
+      }
+      result.push(d[p]);
+    }
+
+    return result;
+  }
+
+  function pointInside(polylines, [x, y]) {
+    let inside = false;
+    for (const polyline of polylines) {
+      for (let i = 0, j = polyline.length - 1; i < polyline.length; j = i++) {
+        const [xi, yi] = polyline[i];
+        const [xj, yj] = polyline[j];
+        if (yi > y !== yj > y && x < ((xj - xi) * (y - yi)) / (yj - yi) + xi) {
+          inside = !inside;
+        }
+      }
+    }
+    return inside;
+  }
+
+  function unsupported(name) {
+    return () => {
+      throw new Error(`bt.${name} is not supported outside the Blot editor`);
+    };
+  }
+
+  return {
+    Turtle,
+    rand,
+    setRandSeed,
+    randInRange,
+    randIntInRange,
+    noise,
+    copy,
+    bounds,
+    translate,
+    rotate,
+    scale,
+    originate,
+    join,
+    iteratePoints,
+    getPoint,
+    getAngle,
+    getNormal,
+    trim,
+    resample,
+    simplify,
+    catmullRom,
+    nurbs,
+    pointInside,
+    cut: unsupported("cut"),
+    cover: unsupported("cover"),
+    union: unsupported("union"),
+    difference: unsupported("difference"),
+    intersection: unsupported("intersection"),
+    xor: unsupported("xor"),
+    offset: unsupported("offset"),
+  };
+})();
+
+const blotToolkit = bt;
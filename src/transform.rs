@@ -0,0 +1,275 @@
+//! Moving, turning and resizing a whole drawing before it's plotted
+
+use std::str::FromStr;
+
+use crate::bed::Bed;
+use crate::plot::{Affine, Point, Polyline};
+
+/// How to resize a drawing to fit the bed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fit {
+    /// As large as it can be while fitting inside the bed
+    Bed,
+    /// As wide as the bed
+    Width,
+    /// As tall as the bed
+    Height,
+}
+
+impl FromStr for Fit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bed" => Ok(Fit::Bed),
+            "width" => Ok(Fit::Width),
+            "height" => Ok(Fit::Height),
+            _ => Err(format!(
+                "Drawings can fit the bed, width or height, not {}",
+                s
+            )),
+        }
+    }
+}
+
+/// Changes to a drawing, made in the order of the fields
+///
+/// Mirroring, scaling and rotating keep the bottom left corner of the drawing's bounds where
+/// it was, so a drawing on the bed stays there. Fitting then resizes the drawing and centres
+/// it on the bed, and translating moves it last of all.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    /// Flip left to right
+    pub mirror_x: bool,
+    /// Flip top to bottom
+    pub mirror_y: bool,
+    pub scale: f32,
+    /// Degrees to rotate counterclockwise
+    pub rotate: f32,
+    pub fit: Option<Fit>,
+    /// Distance to move along x and y, in millimetres
+    pub translate: Point,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            mirror_x: false,
+            mirror_y: false,
+            scale: 1.0,
+            rotate: 0.0,
+            fit: None,
+            translate: (0.0, 0.0),
+        }
+    }
+}
+
+impl Transform {
+    pub fn apply(&self, polylines: Vec<Polyline>, bed: &Bed) -> Vec<Polyline> {
+        if *self == Transform::default() {
+            return polylines;
+        }
+        let Some((min, _)) = bounds(&polylines) else {
+            return polylines;
+        };
+
+        let flip = |mirror| if mirror { -1.0 } else { 1.0 };
+        let (sin, cos) = self.rotate.to_radians().sin_cos();
+        let shape = Affine::new(
+            flip(self.mirror_x) * self.scale,
+            0.0,
+            0.0,
+            flip(self.mirror_y) * self.scale,
+            0.0,
+            0.0,
+        )
+        .then(&Affine::new(cos, sin, -sin, cos, 0.0, 0.0));
+
+        let shaped: Vec<Polyline> = polylines
+            .into_iter()
+            .map(|polyline| polyline.into_iter().map(|p| shape.apply(p)).collect())
+            .collect();
+        let (shaped_min, shaped_max) = bounds(&shaped).unwrap();
+
+        let placement = match self.fit {
+            Some(fit) => fit_to(fit, bed, shaped_min, shaped_max),
+            None => translation((min.0 - shaped_min.0, min.1 - shaped_min.1)),
+        }
+        .then(&translation(self.translate));
+
+        shaped
+            .into_iter()
+            .map(|polyline| polyline.into_iter().map(|p| placement.apply(p)).collect())
+            .collect()
+    }
+}
+
+/// The bottom left and top right corners of the box around a drawing, if it has any points
+pub fn bounds(polylines: &[Polyline]) -> Option<(Point, Point)> {
+    polylines.iter().flatten().fold(None, |bounds, &(x, y)| {
+        let ((min_x, min_y), (max_x, max_y)) = bounds.unwrap_or(((x, y), (x, y)));
+        Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))))
+    })
+}
+
+// Scales the box from `min` to `max` to fit the bed, and centres it there
+fn fit_to(fit: Fit, bed: &Bed, min: Point, max: Point) -> Affine {
+    let (width, height) = (max.0 - min.0, max.1 - min.1);
+    let to_width = bed.width / width;
+    let to_height = bed.height / height;
+
+    // A flat drawing, such as a single horizontal line, can only be fitted along its length
+    let factors = match fit {
+        Fit::Bed => vec![to_width, to_height],
+        Fit::Width => vec![to_width],
+        Fit::Height => vec![to_height],
+    };
+    let factor = factors
+        .into_iter()
+        .filter(|factor| factor.is_finite())
+        .reduce(f32::min)
+        .unwrap_or(1.0);
+
+    Affine::new(
+        factor,
+        0.0,
+        0.0,
+        factor,
+        (bed.width - width * factor) / 2.0 - min.0 * factor,
+        (bed.height - height * factor) / 2.0 - min.1 * factor,
+    )
+}
+
+fn translation((x, y): Point) -> Affine {
+    Affine::new(1.0, 0.0, 0.0, 1.0, x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BED: Bed = Bed {
+        width: 100.0,
+        height: 50.0,
+    };
+
+    fn close(a: Point, b: Point) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    // An L shape from (10, 20) up to (10, 30) and across to (14, 20)
+    fn shape() -> Vec<Polyline> {
+        vec![vec![(10.0, 30.0), (10.0, 20.0), (14.0, 20.0)]]
+    }
+
+    fn points(polylines: Vec<Polyline>) -> Polyline {
+        polylines.into_iter().flatten().collect()
+    }
+
+    #[test]
+    fn default_changes_nothing() {
+        assert_eq!(Transform::default().apply(shape(), &BED), shape());
+    }
+
+    #[test]
+    fn mirroring_keeps_the_bottom_left_corner() {
+        let transform = Transform {
+            mirror_x: true,
+            ..Transform::default()
+        };
+        let result = points(transform.apply(shape(), &BED));
+
+        assert!(close(result[0], (14.0, 30.0)));
+        assert!(close(result[1], (14.0, 20.0)));
+        assert!(close(result[2], (10.0, 20.0)));
+    }
+
+    #[test]
+    fn scaling_keeps_the_bottom_left_corner() {
+        let transform = Transform {
+            scale: 2.0,
+            ..Transform::default()
+        };
+        let result = points(transform.apply(shape(), &BED));
+
+        assert!(close(result[0], (10.0, 40.0)));
+        assert!(close(result[1], (10.0, 20.0)));
+        assert!(close(result[2], (18.0, 20.0)));
+    }
+
+    #[test]
+    fn rotating_keeps_the_bottom_left_corner() {
+        let transform = Transform {
+            rotate: 90.0,
+            ..Transform::default()
+        };
+        let result = points(transform.apply(shape(), &BED));
+
+        // The upright stroke now points left, and the foot points up
+        assert!(close(result[0], (10.0, 20.0)));
+        assert!(close(result[1], (20.0, 20.0)));
+        assert!(close(result[2], (20.0, 24.0)));
+    }
+
+    #[test]
+    fn scaling_happens_before_rotating() {
+        let transform = Transform {
+            scale: 2.0,
+            rotate: 90.0,
+            ..Transform::default()
+        };
+        let (min, max) = bounds(&transform.apply(shape(), &BED)).unwrap();
+
+        assert!(close(min, (10.0, 20.0)));
+        assert!(close(max, (30.0, 28.0)));
+    }
+
+    #[test]
+    fn fit_centres_on_the_bed() {
+        let transform = Transform {
+            fit: Some(Fit::Bed),
+            ..Transform::default()
+        };
+        let (min, max) = bounds(&transform.apply(shape(), &BED)).unwrap();
+
+        // 4 by 10 can grow 5 times before it's as tall as the bed
+        assert!(close(min, (40.0, 0.0)));
+        assert!(close(max, (60.0, 50.0)));
+    }
+
+    #[test]
+    fn fitting_a_flat_drawing_uses_its_length() {
+        let transform = Transform {
+            fit: Some(Fit::Bed),
+            ..Transform::default()
+        };
+        let line = vec![vec![(0.0, 5.0), (10.0, 5.0)]];
+        let result = points(transform.apply(line, &BED));
+
+        assert!(close(result[0], (0.0, 25.0)));
+        assert!(close(result[1], (100.0, 25.0)));
+    }
+
+    #[test]
+    fn translating_happens_after_fitting() {
+        let transform = Transform {
+            fit: Some(Fit::Width),
+            translate: (-5.0, 3.0),
+            ..Transform::default()
+        };
+        let line = vec![vec![(0.0, 0.0), (10.0, 0.0)]];
+        let result = points(transform.apply(line, &BED));
+
+        assert!(close(result[0], (-5.0, 28.0)));
+        assert!(close(result[1], (95.0, 28.0)));
+    }
+
+    #[test]
+    fn empty_drawing_is_left_alone() {
+        let transform = Transform {
+            fit: Some(Fit::Bed),
+            ..Transform::default()
+        };
+        assert!(transform.apply(vec![], &BED).is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+use serialport::{SerialPortType, UsbPortInfo};
+
+use crate::sim;
+
+// How long a read waits for data before giving up, which is also how long newly queued
+// packets can wait for the comms task to notice them
+const READ_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// A byte stream that packets can be exchanged with the Blot over
+pub trait Transport: Read + Write + Send {}
+
+impl<T: Read + Write + Send + ?Sized> Transport for T {}
+
+/// Opens the transport described by a port string
+///
+/// `tcp://host:port` and `unix:///path/to/socket` connect to a serial bridge such as ser2net,
+/// `sim://` starts a simulated Blot, and anything else is opened as a serial port.
+pub fn open(port: &str) -> io::Result<Box<dyn Transport>> {
+    if let Some(addr) = port.strip_prefix("tcp://") {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+
+        return Ok(Box::new(stream));
+    }
+
+    #[cfg(unix)]
+    if let Some(path) = port.strip_prefix("unix://") {
+        let stream = UnixStream::connect(path)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+        return Ok(Box::new(stream));
+    }
+
+    if port.starts_with("sim://") {
+        return Ok(Box::new(sim::spawn()));
+    }
+
+    let serial = serialport::new(port, 9600).timeout(READ_TIMEOUT).open()?;
+
+    Ok(Box::new(serial))
+}
+
+/// Opens a port again after the connection to it was lost
+///
+/// USB serial ports can come back under a different name, such as `/dev/ttyACM1` instead of
+/// `/dev/ttyACM0`, so the device's vendor ID, product ID and serial number are remembered
+/// when the port is first opened and used to find it again.
+pub struct Reconnect {
+    port: String,
+    usb: Option<UsbPortInfo>,
+}
+
+impl Reconnect {
+    pub fn new(port: &str) -> Reconnect {
+        let usb = serialport::available_ports()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|p| p.port_name == port)
+            .and_then(|p| match p.port_type {
+                SerialPortType::UsbPort(info) => Some(info),
+                _ => None,
+            });
+
+        Reconnect {
+            port: port.to_string(),
+            usb,
+        }
+    }
+
+    /// Tries to open the port, returning the name it was found under
+    pub fn open(&self) -> io::Result<(String, Box<dyn Transport>)> {
+        let error = match open(&self.port) {
+            Ok(transport) => return Ok((self.port.clone(), transport)),
+            Err(e) => e,
+        };
+
+        let Some(usb) = &self.usb else {
+            return Err(error);
+        };
+
+        let same_device = serialport::available_ports()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|p| match &p.port_type {
+                SerialPortType::UsbPort(info) => {
+                    info.vid == usb.vid
+                        && info.pid == usb.pid
+                        && info.serial_number == usb.serial_number
+                }
+                _ => false,
+            });
+
+        match same_device {
+            Some(p) => open(&p.port_name).map(|transport| (p.port_name, transport)),
+            None => Err(error),
+        }
+    }
+}
+
+/// One end of an in-memory byte pipe
+pub struct PipeTransport {
+    tx: Sender<Vec<u8>>,
+    rx: Receiver<Vec<u8>>,
+    pending: Vec<u8>,
+}
+
+/// Creates a connected pair of in-memory transports
+///
+/// Bytes written to one end can be read from the other.
+pub fn pipe() -> (PipeTransport, PipeTransport) {
+    let (a_tx, b_rx) = mpsc::channel();
+    let (b_tx, a_rx) = mpsc::channel();
+
+    (
+        PipeTransport {
+            tx: a_tx,
+            rx: a_rx,
+            pending: vec![],
+        },
+        PipeTransport {
+            tx: b_tx,
+            rx: b_rx,
+            pending: vec![],
+        },
+    )
+}
+
+impl Read for PipeTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            match self.rx.recv_timeout(READ_TIMEOUT) {
+                Ok(bytes) => self.pending = bytes,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(io::Error::from(io::ErrorKind::TimedOut))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(io::Error::from(io::ErrorKind::BrokenPipe))
+                }
+            }
+        }
+
+        let len = buf.len().min(self.pending.len());
+        buf[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.drain(..len);
+
+        Ok(len)
+    }
+}
+
+impl Write for PipeTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.tx
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}